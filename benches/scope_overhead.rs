@@ -0,0 +1,100 @@
+//! cost of timing one scope, ``cargo bench --bench scope_overhead``,
+//! every iteration is one frame of ``SCOPES`` scopes so criterion reports the time per scope
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use triglyceride::{init_profiler, lone_scope, open_profiler, scope, Settings};
+
+init_profiler!(PROF, Settings::default());
+
+const SCOPES: u64 = 100;
+
+/// frames recorded between applying them, well under what makes a thread apply its own events
+const FRAMES_PER_APPLY: u64 = 64;
+
+
+/// what the instrumented thread pays, the recorded frames are applied outside the timed part
+/// the way the ui thread applies them when it opens the profiler
+fn recorded<F: FnMut()>(iters: u64, mut frame: F) -> Duration {
+   let mut total = Duration::ZERO;
+
+   let mut done = 0;
+   while done < iters {
+      let frames = FRAMES_PER_APPLY.min(iters - done);
+
+      let start = Instant::now();
+      for _ in 0..frames {
+         frame();
+      }
+      total += start.elapsed();
+
+      open_profiler(&PROF, |_| ());
+      done += frames;
+   }
+
+   total
+}
+
+fn per_scope(c: &mut Criterion) {
+   let mut group = c.benchmark_group("per scope");
+   group.throughput(Throughput::Elements(SCOPES));
+
+   // every benchmark shares the outermost event so each iteration is a frame to the profiler
+   group.bench_function("scope", |b| b.iter_custom(|iters| recorded(iters, || {
+      let _frame = scope(&PROF, "FRAME");
+      for _ in 0..SCOPES {
+         let _guard = scope(&PROF, black_box("EVENT"));
+      }
+   })));
+
+   group.bench_function("nested scope", |b| b.iter_custom(|iters| recorded(iters, || {
+      let _frame = scope(&PROF, "FRAME");
+      for _ in 0..SCOPES / 2 {
+         let _outer = scope(&PROF, black_box("OUTER"));
+         let _inner = scope(&PROF, black_box("INNER"));
+      }
+   })));
+
+   group.bench_function("lone scope", |b| b.iter_custom(|iters| recorded(iters, || {
+      let _frame = scope(&PROF, "FRAME");
+      for _ in 0..SCOPES {
+         let _guard = lone_scope(&PROF, black_box("LONE"));
+      }
+   })));
+
+   // the floor for any scope, a start and an end timestamp
+   group.bench_function("clock reads", |b| b.iter(|| {
+      for _ in 0..SCOPES {
+         black_box(Instant::now());
+         black_box(Instant::now());
+      }
+   }));
+
+   // recording and applying on the same thread, what a thread pays if nothing else opens the profiler
+   group.bench_function("scope applied inline", |b| b.iter(|| {
+      {
+         let _frame = scope(&PROF, "FRAME");
+         for _ in 0..SCOPES {
+            let _guard = scope(&PROF, black_box("EVENT"));
+         }
+      }
+      open_profiler(&PROF, |_| ());
+   }));
+
+   // taking the lock for every start and end, what ``scope`` did before events were buffered
+   group.bench_function("locked", |b| b.iter(|| {
+      let _ = open_profiler(&PROF, |mut p| p.time_event_start("FRAME"));
+      for _ in 0..SCOPES {
+         let _ = open_profiler(&PROF, |mut p| p.time_event_start(black_box("LOCKED")));
+         let _ = open_profiler(&PROF, |mut p| p.time_event_end(black_box("LOCKED")));
+      }
+      let _ = open_profiler(&PROF, |mut p| p.time_event_end("FRAME"));
+   }));
+
+   group.finish();
+}
+
+criterion_group!(benches, per_scope);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use triglyceride::{HeadlessReporter, init_profiler, open_profiler, Settings, time_event, time_event_mac};
+
+init_profiler!(PROF, Settings::default());
+
+fn main() {
+   // prints the tree every update interval, no window needed
+   HeadlessReporter::stdout(&PROF).spawn();
+
+   loop {
+      serve();
+      open_profiler(&PROF, |mut p| p.resolve_profiler(true));
+   }
+}
+
+#[time_event(PROF, "SERVE")]
+fn serve() {
+   time_event_mac!(PROF, "PARSE", {
+      sleep(Duration::from_millis(1));
+   });
+
+   time_event_mac!(PROF, "RESPOND", {
+      sleep(Duration::from_millis(3));
+   });
+}
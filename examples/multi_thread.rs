@@ -0,0 +1,55 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use eframe::{App, Frame, NativeOptions};
+use egui::Context;
+
+use triglyceride::{open_profiler, Settings, time_event, time_event_mac};
+use triglyceride::init_profiler;
+
+fn main() {
+   // every thread that times something gets its own lane and tree
+   std::thread::Builder::new()
+       .name("AssetLoader".to_string())
+       .spawn(|| loop {
+          load_assets();
+       })
+       .expect("failed to spawn loader");
+
+   eframe::run_native(
+      "MultiThread",
+      NativeOptions::default(),
+      Box::new(|_cc| Ok(Box::new(MainLoop))),
+   ).expect("Failed to run");
+}
+
+init_profiler!(PROF, Settings::default());
+
+#[time_event(PROF, "LOAD_ASSETS")]
+fn load_assets() {
+   time_event_mac!(PROF, "DECODE", {
+      sleep(Duration::from_millis(3));
+   });
+
+   time_event_mac!(PROF, "UPLOAD", {
+      sleep(Duration::from_millis(1));
+   });
+}
+
+struct MainLoop;
+impl App for MainLoop {
+   #[time_event(PROF, "MAIN")]
+   fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+      time_event_mac!(PROF, "DISPLAY", {
+         open_profiler(&PROF, |mut p| {
+            p.display_floating_window(ctx);
+         });
+      });
+
+      time_event_mac!(PROF, "RENDER", {
+         sleep(Duration::from_millis(2));
+      });
+
+      ctx.request_repaint();
+   }
+}
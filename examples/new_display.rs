@@ -21,6 +21,7 @@ fn main() {
 
 init_profiler!(PROF, Settings::default());
 
+#[derive(Default)]
 pub struct TestTreePass;
 impl TestTreePass {
    pub fn new() -> Self {
@@ -0,0 +1,27 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use triglyceride::{init_profiler, open_profiler, ProfilerServer, Settings, time_event, time_event_mac};
+
+init_profiler!(PROF, Settings::default());
+
+fn main() -> std::io::Result<()> {
+   // view it with ``cargo run --bin triglyceride-viewer 127.0.0.1:7878``, the app itself needs no window
+   ProfilerServer::bind(&PROF, "127.0.0.1:7878")?.spawn();
+
+   loop {
+      update();
+      open_profiler(&PROF, |mut p| p.resolve_profiler(true));
+   }
+}
+
+#[time_event(PROF, "UPDATE")]
+fn update() {
+   time_event_mac!(PROF, "PHYSICS", {
+      sleep(Duration::from_millis(2));
+   });
+
+   time_event_mac!(PROF, "RENDER", {
+      sleep(Duration::from_millis(5));
+   });
+}
@@ -0,0 +1,75 @@
+//! shows the ui without the profiled app embedding it, either for a session file saved with
+//! ``save_session`` or the Session menu, or live from an app running a ``ProfilerServer``,
+//! so headless services never need a window or egui
+//!
+//! ```text
+//! triglyceride-viewer capture.trisession
+//! triglyceride-viewer capture.trisession --baseline before.trisession
+//! triglyceride-viewer 127.0.0.1:7878
+//! triglyceride-viewer unix:/tmp/app.sock
+//! ```
+
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::RwLock;
+
+use triglyceride::{connect_remote, open_profiler, run_window, PerformanceProfiler};
+
+
+const USAGE: &str = "usage: triglyceride-viewer <session file | host:port | unix:path> [--baseline <session file>]";
+
+fn main() -> ExitCode {
+   let args: Vec<String> = std::env::args().skip(1).collect();
+
+   let (target, baseline) = match args.as_slice() {
+      [target] => (target, None),
+      [target, flag, baseline] if flag == "--baseline" => (target, Some(baseline)),
+      _ => {
+         eprintln!("{USAGE}");
+         return ExitCode::FAILURE;
+      }
+   };
+
+   let prof = match open(target) {
+      Ok(prof) => prof,
+      Err(error) => {
+         eprintln!("failed to open {target}: {error}");
+         return ExitCode::FAILURE;
+      }
+   };
+
+   if let Some(baseline) = baseline {
+      match PerformanceProfiler::load_session(baseline) {
+         Ok(loaded) => open_profiler(prof, |mut p| p.baseline = Some(Box::new(loaded))),
+         Err(error) => {
+            eprintln!("failed to open baseline {baseline}: {error}");
+            return ExitCode::FAILURE;
+         }
+      }
+   }
+
+   match run_window(prof, &format!("triglyceride - {target}")) {
+      Ok(()) => ExitCode::SUCCESS,
+      Err(error) => {
+         eprintln!("{error}");
+         ExitCode::FAILURE
+      }
+   }
+}
+
+/// files are sessions, anything else is an address to connect to
+fn open(target: &str) -> io::Result<&'static RwLock<PerformanceProfiler>> {
+   let path = Path::new(target);
+   if path.is_file() || path.extension().is_some_and(|extension| extension == "trisession") {
+      return Ok(PerformanceProfiler::load_session(target)?.into_static());
+   }
+
+   match target.strip_prefix("unix:") {
+      #[cfg(unix)]
+      Some(path) => triglyceride::connect_remote_unix(path),
+      #[cfg(not(unix))]
+      Some(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "unix sockets need a unix")),
+      None => connect_remote(target),
+   }
+}
@@ -0,0 +1,34 @@
+use crate::StatString;
+
+
+/// a single timed call of an event, times are in ms since the profiler started
+#[derive(Debug, Clone)]
+pub struct Span {
+   pub name: StatString,
+   pub start: f64,
+   pub end: f64,
+
+   /// nesting depth, 0 is the outermost event of the frame
+   pub depth: u32,
+}
+impl Span {
+   pub fn duration(&self) -> f64 {
+      self.end - self.start
+   }
+}
+
+
+/// every event span recorded between two starts of a threads outermost event, in start order
+#[derive(Debug, Clone, Default)]
+pub struct CapturedFrame {
+   /// counts up from 0 for every frame a thread stores, stays the same once the frame leaves the buffer
+   pub number: u64,
+   pub start: f64,
+   pub end: f64,
+   pub spans: Vec<Span>,
+}
+impl CapturedFrame {
+   pub fn duration(&self) -> f64 {
+      self.end - self.start
+   }
+}
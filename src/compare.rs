@@ -0,0 +1,182 @@
+use crate::{FunctionProfile, PathId, PerformanceProfiler, StatString, ThreadProfile};
+
+
+/// one function compared between a baseline and the current data, times are mean ms per frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FunctionDiff {
+   pub name: StatString,
+
+   /// ``None`` if the function isn't in the baseline
+   pub baseline: Option<f64>,
+
+   /// ``None`` if the function isn't in the current data anymore
+   pub current: Option<f64>,
+
+   /// current minus baseline, missing sides count as 0
+   pub delta: f64,
+
+   /// delta as a share of the baseline in percent, infinite for new functions
+   pub percent: f64,
+
+   /// welch's t-test over the per frame samples of both sides passes at about 95% confidence
+   pub significant: bool,
+}
+impl FunctionDiff {
+   /// compares the last ``intervals`` resolved intervals of each side
+   /// ```
+   /// use triglyceride::{FunctionDiff, FunctionProfile, FunctionStats};
+   ///
+   /// fn profile(samples: &[f64]) -> FunctionProfile {
+   ///    let mut profile = FunctionProfile::default();
+   ///    profile.stats.push(FunctionStats::from_samples(samples));
+   ///    profile
+   /// }
+   ///
+   /// let baseline = profile(&[10.0, 11.0, 9.0, 10.0, 10.0, 11.0, 9.0, 10.0]);
+   /// let noise = profile(&[10.5, 11.0, 9.5, 10.0, 10.5, 11.0, 9.0, 9.5]);
+   /// let slower = profile(&[12.0, 13.0, 11.0, 12.0, 12.0, 13.0, 11.0, 12.0]);
+   ///
+   /// // within the frame to frame spread
+   /// let diff = FunctionDiff::new("WORK", Some(&baseline), Some(&noise), 1);
+   /// assert!(diff.delta > 0.0 && !diff.significant);
+   ///
+   /// let diff = FunctionDiff::new("WORK", Some(&baseline), Some(&slower), 1);
+   /// assert_eq!((diff.delta, diff.percent), (2.0, 20.0));
+   /// assert!(diff.is_regression());
+   ///
+   /// let diff = FunctionDiff::new("NEW", None, Some(&slower), 1);
+   /// assert!(diff.is_regression() && diff.percent.is_infinite());
+   /// ```
+   pub fn new(name: StatString, baseline: Option<&FunctionProfile>, current: Option<&FunctionProfile>, intervals: u32) -> Self {
+      let one = baseline.and_then(|profile| Pooled::of(profile, intervals));
+      let two = current.and_then(|profile| Pooled::of(profile, intervals));
+
+      let delta = two.map(|p| p.mean).unwrap_or(0.0) - one.map(|p| p.mean).unwrap_or(0.0);
+      let percent = match one {
+         Some(one) if one.mean != 0.0 => delta / one.mean * 100.0,
+         _ if delta == 0.0 => 0.0,
+         _ => f64::INFINITY.copysign(delta),
+      };
+
+      let significant = match (one, two) {
+         (Some(one), Some(two)) if one.count > 1 && two.count > 1 => {
+            let standard_error = (one.variance / one.count as f64 + two.variance / two.count as f64).sqrt();
+            match standard_error > 0.0 {
+               true => (delta / standard_error).abs() > T_95,
+               false => delta != 0.0,
+            }
+         }
+
+         // appearing or disappearing entirely is always worth showing
+         (None, Some(_)) | (Some(_), None) => true,
+         _ => false,
+      };
+
+      Self {
+         name,
+         baseline: one.map(|p| p.mean),
+         current: two.map(|p| p.mean),
+         delta,
+         percent,
+         significant,
+      }
+   }
+
+   /// got significantly slower
+   pub fn is_regression(&self) -> bool {
+      self.significant && self.delta > 0.0
+   }
+
+   /// got significantly faster
+   pub fn is_improvement(&self) -> bool {
+      self.significant && self.delta < 0.0
+   }
+}
+
+/// mean and variance of every frame in several resolved intervals together
+#[derive(Debug, Clone, Copy)]
+struct Pooled {
+   mean: f64,
+   variance: f64,
+   count: u32,
+}
+impl Pooled {
+   fn of(profile: &FunctionProfile, intervals: u32) -> Option<Self> {
+      let mut count = 0;
+      let mut sum = 0.0;
+      let mut sum_of_squares = 0.0;
+
+      for stats in profile.stats.iter().rev().take(intervals.max(1) as usize).filter(|stats| stats.count > 0) {
+         let n = stats.count as f64;
+         count += stats.count;
+         sum += n * stats.mean;
+         sum_of_squares += n * (stats.std_dev.powi(2) + stats.mean.powi(2));
+      }
+
+      if count == 0 { return None; }
+
+      let mean = sum / count as f64;
+      Some(Self {
+         mean,
+         variance: (sum_of_squares / count as f64 - mean.powi(2)).max(0.0),
+         count,
+      })
+   }
+}
+
+/// two tailed 95% for large samples
+const T_95: f64 = 1.96;
+
+
+/// comparing a thread against the same thread of a baseline
+impl ThreadProfile {
+   /// every function on either side, merged by name, biggest regressions first
+   pub fn compare_functions(&self, baseline: &ThreadProfile, intervals: u32) -> Vec<FunctionDiff> {
+      let mut names: Vec<StatString> = self.all_profiles.keys().copied().collect();
+      for name in baseline.all_profiles.keys() {
+         if !self.all_profiles.contains_key(name) {
+            names.push(name);
+         }
+      }
+
+      let mut diffs: Vec<FunctionDiff> = names.into_iter()
+          .map(|name| FunctionDiff::new(name, baseline.all_profiles.get(name), self.all_profiles.get(name), intervals))
+          .collect();
+
+      diffs.sort_by(|one, two| two.delta.total_cmp(&one.delta));
+      diffs
+   }
+
+   /// the call path in ``other`` with the same chain of function names, ids differ between threads and sessions
+   pub fn matching_path(&self, path: PathId, other: &ThreadProfile) -> Option<PathId> {
+      let mut matching = None;
+      for name in self.call_paths.chain(path) {
+         matching = Some(other.call_paths.get(matching, name)?);
+      }
+
+      matching
+   }
+
+   /// one call path compared against the same call path of the baseline
+   pub fn compare_path(&self, path: PathId, baseline: &ThreadProfile, intervals: u32) -> FunctionDiff {
+      let baseline_profile = self.matching_path(path, baseline)
+          .and_then(|matching| baseline.path_profiles.get(matching.index()));
+
+      FunctionDiff::new(self.call_paths.name(path), baseline_profile, self.path_profiles.get(path.index()), intervals)
+   }
+}
+
+impl PerformanceProfiler {
+   /// the baseline thread with the same name as ``lane``, thread ids never survive a saved session
+   pub fn baseline_thread(&self, lane: &ThreadProfile) -> Option<&ThreadProfile> {
+      self.baseline.as_ref()?.threads.iter().find(|base| base.name == lane.name)
+   }
+
+   /// keeps a copy of the current data to compare later data against
+   pub fn set_baseline_from_live(&mut self) -> std::io::Result<()> {
+      let mut session = vec![];
+      self.write_session(&mut session)?;
+      self.baseline = Some(Box::new(PerformanceProfiler::read_session(session.as_slice())?));
+      Ok(())
+   }
+}
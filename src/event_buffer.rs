@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::sync::{PoisonError, RwLock, TryLockError};
+use std::thread::{self, Thread};
+
+use instant::Instant;
+
+use crate::function_profile::get_ct;
+use crate::{open_profiler, PerformanceProfiler, StatString};
+
+
+/// one timestamped start or end, the name is a ``&'static str`` so it doubles as a cheap id,
+/// the raw ``Instant`` is kept and only turned into ms when the event is applied
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Event {
+   pub(crate) name: StatString,
+   pub(crate) at: Instant,
+   pub(crate) kind: EventKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EventKind {
+   /// tree event
+   Start,
+   End,
+
+   /// general function outside the tree
+   LoneStart,
+   LoneEnd,
+
+   /// one call of a general function timed elsewhere, like the wall time of a future, in ms
+   Elapsed(f64),
+}
+
+
+/// a threads events up to the end of its outermost open event, waiting in ``PerformanceProfiler::pending``
+#[derive(Debug)]
+pub(crate) struct Batch {
+   pub(crate) thread: Thread,
+   pub(crate) events: Vec<Event>,
+}
+
+/// handed over batches, the recording threads only push here, the aggregation happens in ``open_profiler``
+#[derive(Debug, Default)]
+pub(crate) struct Pending {
+   pub(crate) batches: Vec<Batch>,
+   events: usize,
+
+   /// ms the recording threads waited to hand over their batches
+   lock_wait: f64,
+}
+impl Pending {
+   /// the batches and the time spent waiting to hand them over
+   pub(crate) fn take(&mut self) -> (Vec<Batch>, f64) {
+      self.events = 0;
+      (std::mem::take(&mut self.batches), std::mem::take(&mut self.lock_wait))
+   }
+}
+
+/// events held before applying them on the recording thread, in case nothing ever opens the profiler
+const MAX_PENDING: usize = 1 << 16;
+
+
+/// events of one thread waiting to be handed to the profiler, recording only touches this,
+/// the profiler is only touched when the outermost open event ends, so once a frame for an event loop
+struct EventBuffer {
+   profiler: Option<&'static RwLock<PerformanceProfiler>>,
+   thread: Thread,
+   events: Vec<Event>,
+
+   /// open tree events and lone functions
+   depth: u32,
+}
+impl EventBuffer {
+   fn new() -> Self {
+      Self {
+         profiler: None,
+         thread: thread::current(),
+         events: Vec::with_capacity(FLUSH_AT),
+         depth: 0,
+      }
+   }
+
+   fn record(&mut self, profiler: &'static RwLock<PerformanceProfiler>, event: Event) {
+      // events can only be replayed into the profiler they were recorded for
+      if self.profiler.is_some_and(|current| !std::ptr::eq(current, profiler)) {
+         self.flush(true);
+      }
+      self.profiler = Some(profiler);
+
+      self.events.push(event);
+      match event.kind {
+         EventKind::Start | EventKind::LoneStart => self.depth += 1,
+         EventKind::End | EventKind::LoneEnd => self.depth = self.depth.saturating_sub(1),
+         EventKind::Elapsed(_) => {}
+      }
+
+      // a scope around the whole program would otherwise never flush
+      if self.depth == 0 || self.events.len() >= FLUSH_AT {
+         // past ``MAX_PENDING`` the thread stops outrunning whoever holds the profiler open and waits
+         self.flush(self.events.len() >= MAX_PENDING);
+      }
+   }
+
+   /// a read lock and an uncontended mutex, the write lock is left to whoever opens the profiler next,
+   /// without ``wait`` the events stay buffered while the profiler is open (the ui holds it for its whole draw)
+   /// and go with the next flush
+   #[cold]
+   fn flush(&mut self, wait: bool) {
+      let Some(profiler) = self.profiler else { return; };
+      if self.events.is_empty() { return; }
+
+      let overflowing = {
+         let start = get_ct();
+         let prof = match profiler.try_read() {
+            Ok(prof) => prof,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) if !wait => return,
+            Err(TryLockError::WouldBlock) => profiler.read().unwrap_or_else(PoisonError::into_inner),
+         };
+         let mut pending = prof.pending.lock().unwrap_or_else(PoisonError::into_inner);
+
+         let capacity = self.events.len();
+         let events = std::mem::replace(&mut self.events, Vec::with_capacity(capacity));
+         pending.lock_wait += get_ct() - start;
+         pending.events += events.len();
+         pending.batches.push(Batch { thread: self.thread.clone(), events });
+         pending.events >= MAX_PENDING
+      };
+
+      if overflowing {
+         open_profiler(profiler, |_| ());
+      }
+   }
+}
+impl Drop for EventBuffer {
+   /// hands over whatever a thread left open when it exits
+   fn drop(&mut self) {
+      self.flush(true);
+   }
+}
+
+/// events held before flushing even if the outermost event is still open
+const FLUSH_AT: usize = 4096;
+
+thread_local! {
+   static BUFFER: RefCell<EventBuffer> = RefCell::new(EventBuffer::new());
+}
+
+
+/// records an event on the calling threads buffer, without taking the profilers lock unless it flushes
+#[inline]
+pub(crate) fn record(profiler: &'static RwLock<PerformanceProfiler>, name: StatString, kind: EventKind) {
+   push(profiler, Event { name, at: Instant::now(), kind });
+}
+
+/// records one call of a general function timed elsewhere
+pub(crate) fn record_elapsed(profiler: &'static RwLock<PerformanceProfiler>, name: StatString, elapsed_ms: f64) {
+   push(profiler, Event { name, at: Instant::now(), kind: EventKind::Elapsed(elapsed_ms) });
+}
+
+/// hands over the calling threads buffer, open events included, so anything applied to ``profiler``
+/// straight away lands after what the thread recorded before it
+pub(crate) fn flush_current_thread(profiler: &'static RwLock<PerformanceProfiler>) {
+   let _ = BUFFER.try_with(|buffer| {
+      // already borrowed when a flush of this thread is what opened the profiler
+      let Ok(mut buffer) = buffer.try_borrow_mut() else { return; };
+      if buffer.profiler.is_some_and(|current| std::ptr::eq(current, profiler)) {
+         buffer.flush(true);
+      }
+   });
+}
+
+fn push(profiler: &'static RwLock<PerformanceProfiler>, event: Event) {
+   let buffered = BUFFER.try_with(|buffer| match buffer.try_borrow_mut() {
+      Ok(mut buffer) => {
+         buffer.record(profiler, event);
+         true
+      }
+      Err(_) => false,
+   });
+
+   // thread local already torn down, or a drop running inside a flush, goes straight to the profiler
+   if buffered != Ok(true) {
+      open_profiler(profiler, |mut p| p.replay(&thread::current(), &[event]));
+   }
+}
@@ -0,0 +1,81 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::PerformanceProfiler;
+use crate::utils::json::json_string;
+
+
+/// chrome trace event format, opens in chrome://tracing and https://ui.perfetto.dev
+impl PerformanceProfiler {
+   /// writes every stored frame of every thread as complete ``"X"`` events, timestamps are in microseconds
+   /// ```
+   /// use triglyceride::{CapturedFrame, PerformanceProfiler, Settings, Span, ThreadProfile};
+   ///
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   /// lane.frames.push_back(CapturedFrame {
+   ///    number: 0,
+   ///    start: 1.0,
+   ///    end: 11.0,
+   ///    spans: vec![
+   ///       Span { name: "FRAME", start: 1.0, end: 11.0, depth: 0 },
+   ///       Span { name: "WORK", start: 2.5, end: 4.0, depth: 1 },
+   ///    ],
+   /// });
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings::default());
+   /// profiler.threads.push(lane);
+   ///
+   /// let mut json = vec![];
+   /// profiler.write_chrome_trace(&mut json).unwrap();
+   /// let json = String::from_utf8(json).unwrap();
+   ///
+   /// assert!(json.contains(r#""args":{"name":"main"}"#));
+   /// assert!(json.contains(r#""name":"FRAME","cat":"triglyceride","ph":"X","ts":1000.000,"dur":10000.000"#));
+   /// assert!(json.contains(r#""name":"WORK","cat":"triglyceride","ph":"X","ts":2500.000,"dur":1500.000"#));
+   /// ```
+   pub fn write_chrome_trace<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+      let pid = std::process::id();
+      let mut first = true;
+
+      write!(writer, "{{\"traceEvents\":[")?;
+
+      for (tid, lane) in self.threads.iter().enumerate() {
+         separator(&mut writer, &mut first)?;
+         write!(
+            writer,
+            "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{pid},\"tid\":{tid},\"args\":{{\"name\":{}}}}}",
+            json_string(&lane.name),
+         )?;
+
+         for frame in lane.frames.iter() {
+            for span in frame.spans.iter() {
+               separator(&mut writer, &mut first)?;
+               write!(
+                  writer,
+                  "{{\"name\":{},\"cat\":\"triglyceride\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":{pid},\"tid\":{tid}}}",
+                  json_string(span.name),
+                  span.start * 1000.0,
+                  span.duration() * 1000.0,
+               )?;
+            }
+         }
+      }
+
+      write!(writer, "],\"displayTimeUnit\":\"ms\"}}")?;
+      writer.flush()
+   }
+
+   /// writes ``write_chrome_trace`` to a file, replacing it if it exists
+   pub fn export_chrome_trace(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+      self.write_chrome_trace(BufWriter::new(File::create(path)?))
+   }
+}
+
+fn separator<W: Write>(writer: &mut W, first: &mut bool) -> std::io::Result<()> {
+   if !*first {
+      write!(writer, ",")?;
+   }
+   *first = false;
+   Ok(())
+}
@@ -0,0 +1,88 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::{PathId, PerformanceProfiler, ThreadProfile};
+
+
+/// brendan greggs folded stack format, used by flamegraph.pl and inferno
+impl PerformanceProfiler {
+   /// writes one ``ROOT;CHILD;GRANDCHILD 1234`` line per node of every threads latest tree,
+   /// weighted by the nodes self time in whole microseconds, lines are prefixed by the thread name
+   /// once more than one thread is recorded
+   /// ```
+   /// use triglyceride::{FunctionProfile, FunctionStats, PerformanceProfiler, Settings, ThreadProfile};
+   ///
+   /// fn profile(samples: &[f64], self_time: f64) -> FunctionProfile {
+   ///    let mut profile = FunctionProfile::default();
+   ///    profile.stats.push(FunctionStats { self_time, ..FunctionStats::from_samples(samples) });
+   ///    profile.timings.push([0.0, profile.stats[0].mean]);
+   ///    profile
+   /// }
+   ///
+   /// let mut main = ThreadProfile::new(None, "main".to_string());
+   /// let frame = main.call_paths.intern(None, "FRAME");
+   /// let work = main.call_paths.intern(Some(frame), "WORK");
+   ///
+   /// // work only ran in some frames, so the frames self time isn't its mean minus works mean
+   /// main.path_profiles = vec![profile(&[4.0], 2.5), profile(&[1.0, 3.0], 2.0)];
+   /// main.latest_tree.set_root(frame, "FRAME");
+   /// main.latest_tree.add_child(frame, work, "WORK");
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings::default());
+   /// profiler.threads = vec![main];
+   ///
+   /// let mut folded = vec![];
+   /// profiler.write_folded_stacks(&mut folded).unwrap();
+   /// assert_eq!(String::from_utf8(folded).unwrap(), "\
+   /// FRAME;WORK 2000
+   /// FRAME 2500
+   /// ");
+   /// ```
+   pub fn write_folded_stacks<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+      let tag_threads = self.threads.len() > 1;
+
+      for lane in self.threads.iter() {
+         let Some(root) = lane.latest_tree.root else { continue; };
+
+         let mut stack = vec![];
+         if tag_threads {
+            stack.push(lane.name.as_str());
+         }
+
+         self.recursive_folded_stack(&mut writer, lane, root, &mut stack)?;
+      }
+
+      writer.flush()
+   }
+
+   /// writes ``write_folded_stacks`` to a file, replacing it if it exists
+   pub fn export_folded_stacks(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+      self.write_folded_stacks(BufWriter::new(File::create(path)?))
+   }
+
+   fn recursive_folded_stack<'a, W: Write>(
+      &self,
+      writer: &mut W,
+      lane: &'a ThreadProfile,
+      path: PathId,
+      stack: &mut Vec<&'a str>,
+   ) -> std::io::Result<()>
+   {
+      let smoothing = self.settings.smoothing_amount;
+      let Some(tree_node) = lane.latest_tree.nodes.get(&path) else { return Ok(()); };
+      stack.push(tree_node.name);
+
+      for child in tree_node.children.iter() {
+         self.recursive_folded_stack(writer, lane, *child, stack)?;
+      }
+
+      let self_us = (lane.pull_path_self_data(path, smoothing) * 1000.0).round();
+      if self_us >= 1.0 {
+         writeln!(writer, "{} {}", stack.join(";"), self_us as u64)?;
+      }
+
+      stack.pop();
+      Ok(())
+   }
+}
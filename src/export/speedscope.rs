@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::{PerformanceProfiler, Span, StatString};
+use crate::utils::json::json_string;
+
+
+/// speedscope file format, opens in https://www.speedscope.app
+impl PerformanceProfiler {
+   /// writes every stored frame as one evented profile per thread, times are in milliseconds
+   /// ```
+   /// use triglyceride::{CapturedFrame, PerformanceProfiler, Settings, Span, ThreadProfile};
+   ///
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   /// lane.frames.push_back(CapturedFrame {
+   ///    number: 0,
+   ///    start: 0.0,
+   ///    end: 10.0,
+   ///    spans: vec![
+   ///       Span { name: "FRAME", start: 0.0, end: 10.0, depth: 0 },
+   ///       Span { name: "LOAD", start: 1.0, end: 3.0, depth: 1 },
+   ///       Span { name: "DRAW", start: 4.0, end: 8.0, depth: 1 },
+   ///       Span { name: "UPLOAD", start: 5.0, end: 6.0, depth: 2 },
+   ///    ],
+   /// });
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings::default());
+   /// profiler.threads.push(lane);
+   ///
+   /// let mut json = vec![];
+   /// profiler.write_speedscope(&mut json).unwrap();
+   /// let json = String::from_utf8(json).unwrap();
+   ///
+   /// // a sibling closes before the next one opens, a child closes before its parent
+   /// let events = [
+   ///    ("O", 0, 0.0), ("O", 1, 1.0), ("C", 1, 3.0),
+   ///    ("O", 2, 4.0), ("O", 3, 5.0), ("C", 3, 6.0), ("C", 2, 8.0),
+   ///    ("C", 0, 10.0),
+   /// ];
+   /// let events: Vec<String> = events.iter()
+   ///     .map(|(kind, frame, at)| format!(r#"{{"type":"{kind}","frame":{frame},"at":{at:.6}}}"#))
+   ///     .collect();
+   /// assert!(json.contains(&format!(r#""events":[{}]"#, events.join(","))));
+   /// ```
+   pub fn write_speedscope<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+      // shared frame table, every distinct event name once
+      let mut frame_names: Vec<StatString> = vec![];
+      let mut frame_index: HashMap<StatString, usize> = HashMap::new();
+      for lane in self.threads.iter() {
+         for span in lane.frames.iter().flat_map(|frame| frame.spans.iter()) {
+            frame_index.entry(span.name).or_insert_with(|| {
+               frame_names.push(span.name);
+               frame_names.len() - 1
+            });
+         }
+      }
+
+      write!(writer, "{{\"$schema\":\"https://www.speedscope.app/file-format-schema.json\",\"shared\":{{\"frames\":[")?;
+      for (i, name) in frame_names.iter().enumerate() {
+         if i > 0 { write!(writer, ",")?; }
+         write!(writer, "{{\"name\":{}}}", json_string(name))?;
+      }
+      write!(writer, "]}},\"profiles\":[")?;
+
+      for (i, lane) in self.threads.iter().enumerate() {
+         if i > 0 { write!(writer, ",")?; }
+
+         let start = lane.frames.front().map(|frame| frame.start).unwrap_or(0.0);
+         let end = lane.frames.back().map(|frame| frame.end).unwrap_or(start);
+
+         write!(
+            writer,
+            "{{\"type\":\"evented\",\"name\":{},\"unit\":\"milliseconds\",\"startValue\":{start:.6},\"endValue\":{end:.6},\"events\":[",
+            json_string(&lane.name),
+         )?;
+
+         let mut events = EventWriter { writer: &mut writer, first: true, last_at: start };
+         for frame in lane.frames.iter() {
+            events.frame(&frame.spans, &frame_index)?;
+         }
+
+         write!(writer, "]}}")?;
+      }
+
+      write!(
+         writer,
+         "],\"name\":\"triglyceride\",\"activeProfileIndex\":0,\"exporter\":\"triglyceride {}\"}}",
+         env!("CARGO_PKG_VERSION"),
+      )?;
+      writer.flush()
+   }
+
+   /// writes ``write_speedscope`` to a file, replacing it if it exists
+   pub fn export_speedscope(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+      self.write_speedscope(BufWriter::new(File::create(path)?))
+   }
+}
+
+
+/// turns start ordered spans into strictly nested open / close events
+struct EventWriter<'a, W: Write> {
+   writer: &'a mut W,
+   first: bool,
+
+   /// speedscope wants non-decreasing timestamps
+   last_at: f64,
+}
+impl<W: Write> EventWriter<'_, W> {
+   fn frame(&mut self, spans: &[Span], frame_index: &HashMap<StatString, usize>) -> std::io::Result<()> {
+      let mut open: Vec<&Span> = vec![];
+
+      for span in spans.iter() {
+         while let Some(top) = open.last() {
+            if top.depth < span.depth { break; }
+            self.event("C", frame_index[top.name], top.end)?;
+            open.pop();
+         }
+
+         self.event("O", frame_index[span.name], span.start)?;
+         open.push(span);
+      }
+
+      while let Some(top) = open.pop() {
+         self.event("C", frame_index[top.name], top.end)?;
+      }
+
+      Ok(())
+   }
+
+   fn event(&mut self, kind: &str, frame: usize, at: f64) -> std::io::Result<()> {
+      self.last_at = at.max(self.last_at);
+
+      if !self.first { write!(self.writer, ",")?; }
+      self.first = false;
+
+      write!(self.writer, "{{\"type\":\"{kind}\",\"frame\":{frame},\"at\":{:.6}}}", self.last_at)
+   }
+}
@@ -10,54 +10,314 @@ pub struct FunctionProfile {
    st: f64,
    max_stored_cash_amount: u32,
 
+   /// calls, summed time and summed self time of the frame in progress
+   frame_calls: u32,
+   frame_total: f64,
+   frame_self_total: f64,
+
+   /// summed time of each finished frame the function was called in
    average_cash: Vec<f64>,
+   /// calls of each finished frame, lines up with ``average_cash``
+   calls_cash: Vec<u32>,
+   /// summed self time of each finished frame, lines up with ``average_cash``
+   self_cash: Vec<f64>,
 
    /// 0 is a rolling index, used for graphing with ``egui_graph``
    /// 1 is the actual time elapsed in ms
    pub timings: Vec<[f64; 2]>,
+
+   /// full statistics of each resolved interval, lines up index for index with ``timings``
+   pub stats: Vec<FunctionStats>,
 }
 impl Default for FunctionProfile {
    fn default() -> Self {
       Self {
          st: get_ct(),
          max_stored_cash_amount: 10,
+         frame_calls: 0,
+         frame_total: 0.0,
+         frame_self_total: 0.0,
          average_cash: vec![],
+         calls_cash: vec![],
+         self_cash: vec![],
          timings: vec![],
+         stats: vec![],
       }
    }
 }
 impl FunctionProfile {
-   pub(crate) fn start(&mut self) {
-      if (self.average_cash.len() as u32) < self.max_stored_cash_amount {
-         self.st = get_ct();
-      }
+   pub(crate) fn start(&mut self, now: f64) {
+      self.st = now;
+   }
+   pub(crate) fn end(&mut self, now: f64) {
+      self.add_call(now - self.st);
+   }
+
+   /// one call timed somewhere else
+   pub(crate) fn add_call(&mut self, elapsed: f64) {
+      self.frame_calls += 1;
+      self.frame_total += elapsed;
+      self.frame_self_total += elapsed;
    }
-   pub(crate) fn end(&mut self) {
+
+   /// time spent in tree children, lone functions never have any so their self time is their total
+   pub(crate) fn remove_child_time(&mut self, children: f64) {
+      self.frame_self_total -= children;
+   }
+
+   /// folds every call of the finished frame into one sample, so a function called 500 times a frame
+   /// reports what it costs the frame rather than one call
+   pub(crate) fn end_frame(&mut self) {
+      if self.frame_calls == 0 { return; }
+
       if (self.average_cash.len() as u32) < self.max_stored_cash_amount {
-         self.average_cash.push(get_ct() - self.st);
+         self.average_cash.push(self.frame_total);
+         self.calls_cash.push(self.frame_calls);
+         self.self_cash.push(self.frame_self_total.max(0.0));
       }
+
+      self.frame_calls = 0;
+      self.frame_total = 0.0;
+      self.frame_self_total = 0.0;
+   }
+
+   /// drops every frame collected since the last resolve
+   pub(crate) fn discard_frames(&mut self) {
+      self.average_cash.clear();
+      self.calls_cash.clear();
+      self.self_cash.clear();
+
+      self.frame_calls = 0;
+      self.frame_total = 0.0;
+      self.frame_self_total = 0.0;
    }
+
    pub(crate) fn resolve(&mut self, stored_cash_amount: u32, stored_data_amount: u32, cull_first_average: bool, counter: u32) {
       self.max_stored_cash_amount = stored_cash_amount;
 
-      if cull_first_average { self.average_cash.remove(0); }
+      if cull_first_average && !self.average_cash.is_empty() {
+         self.average_cash.remove(0);
+         self.calls_cash.remove(0);
+         self.self_cash.remove(0);
+      }
 
-      let ave: f64 = self.average_cash.iter().sum::<f64>() / self.average_cash.len() as f64;
+      let mut stats = FunctionStats::from_samples(&self.average_cash);
+      let calls: u32 = self.calls_cash.iter().sum();
+      if calls > 0 {
+         stats.calls_per_frame = calls as f64 / self.calls_cash.len() as f64;
+         stats.mean_per_call = self.average_cash.iter().sum::<f64>() / calls as f64;
+         stats.self_time = self.self_cash.iter().sum::<f64>() / self.self_cash.len() as f64;
+      }
 
-      self.timings.push([counter as f64, ave]);
+      self.timings.push([counter as f64, stats.mean]);
+      self.stats.push(stats);
 
       let diff = self.timings.len() as i32 - stored_data_amount as i32;
-      if diff > 0 { self.timings.drain(0..(diff as usize)); }
+      if diff > 0 {
+         self.timings.drain(0..(diff as usize));
+         self.stats.drain(0..(diff as usize));
+      }
 
       self.average_cash.clear();
+      self.calls_cash.clear();
+      self.self_cash.clear();
    }
 
    /// pulls the latest elapsed time in ms from ``FunctionProfile::timings``
    pub fn pull_latest(&self) -> f64 {
       self.timings.last().unwrap_or(&[0.0, 0.0])[1]
    }
+
+   /// pulls the latest self time in ms, the time not spent in child events
+   pub fn pull_latest_self(&self) -> f64 {
+      self.latest_stats().map(|stats| stats.self_time).unwrap_or(0.0)
+   }
+
+   /// statistics of the latest resolved interval
+   pub fn latest_stats(&self) -> Option<&FunctionStats> {
+      self.stats.last()
+   }
+
+   /// one statistic averaged over the last ``smoothing_amount`` intervals, 0.0 before the first resolve
+   pub fn pull_smoothed(&self, statistic: Statistic, smoothing_amount: u32) -> f64 {
+      let mut c = 0;
+      let mut tot = 0.0;
+      for stats in self.stats.iter().rev().take(smoothing_amount.max(1) as usize) {
+         c += 1;
+         tot += stats.get(statistic);
+      }
+
+      if c == 0 { 0.0 } else { tot / c as f64 }
+   }
+
+   /// one statistic over every stored interval, in the same ``[index, ms]`` layout as ``timings``
+   pub fn series(&self, statistic: Statistic) -> Vec<[f64; 2]> {
+      self.timings.iter()
+          .zip(self.stats.iter())
+          .map(|(timing, stats)| [timing[0], stats.get(statistic)])
+          .collect()
+   }
 }
 
-fn get_ct() -> f64 {
+
+/// everything known about one resolved interval of a function, times in ms,
+/// each sample is the summed time of every call in one frame
+/// ```
+/// use triglyceride::{Settings, ThreadProfile};
+///
+/// let settings = Settings { update_interval_sec: 0.0, ..Settings::default() };
+/// let mut lane = ThreadProfile::new(None, "main".to_string());
+///
+/// // 1 ms calls, once in even frames and three times in odd ones
+/// for frame in 0..6 {
+///    lane.time_event_start(&settings, "FRAME").unwrap();
+///    for _ in 0..1 + frame % 2 * 2 {
+///       lane.add_function_call("HASH", 1.0);
+///    }
+///    lane.time_event_end("FRAME").unwrap();
+/// }
+///
+/// let stats = lane.all_profiles["HASH"].latest_stats().unwrap();
+/// assert_eq!((stats.min, stats.max, stats.mean), (1.0, 3.0, 2.0));
+/// assert_eq!((stats.calls_per_frame, stats.mean_per_call), (2.0, 1.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FunctionStats {
+   /// frames sampled
+   pub count: u32,
+   pub calls_per_frame: f64,
+   pub mean_per_call: f64,
+   /// mean time per frame not spent in child events
+   pub self_time: f64,
+   pub mean: f64,
+   pub min: f64,
+   pub max: f64,
+   pub p50: f64,
+   pub p95: f64,
+   pub p99: f64,
+   pub std_dev: f64,
+}
+impl FunctionStats {
+   /// treats every sample as a single call with no children,
+   /// all values are ``NaN`` if there are no samples, same as an interval the function wasn't called in
+   /// ```
+   /// use triglyceride::FunctionStats;
+   ///
+   /// let mut samples = vec![2.0; 99];
+   /// samples.push(40.0);
+   ///
+   /// let stats = FunctionStats::from_samples(&samples);
+   /// assert_eq!(stats.count, 100);
+   /// assert_eq!(stats.p50, 2.0);
+   /// assert_eq!(stats.max, 40.0);
+   /// ```
+   pub fn from_samples(samples: &[f64]) -> Self {
+      if samples.is_empty() {
+         return Self {
+            count: 0,
+            calls_per_frame: 0.0,
+            mean_per_call: f64::NAN,
+            self_time: f64::NAN,
+            mean: f64::NAN,
+            min: f64::NAN,
+            max: f64::NAN,
+            p50: f64::NAN,
+            p95: f64::NAN,
+            p99: f64::NAN,
+            std_dev: f64::NAN,
+         };
+      }
+
+      let mut sorted = samples.to_vec();
+      sorted.sort_by(f64::total_cmp);
+
+      let count = sorted.len();
+      let mean = sorted.iter().sum::<f64>() / count as f64;
+      let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count as f64;
+
+      // nearest rank
+      let percentile = |p: f64| sorted[((p * count as f64).ceil() as usize).clamp(1, count) - 1];
+
+      Self {
+         count: count as u32,
+         calls_per_frame: 1.0,
+         mean_per_call: mean,
+         self_time: mean,
+         mean,
+         min: sorted[0],
+         max: sorted[count - 1],
+         p50: percentile(0.50),
+         p95: percentile(0.95),
+         p99: percentile(0.99),
+         std_dev: variance.sqrt(),
+      }
+   }
+
+   pub fn get(&self, statistic: Statistic) -> f64 {
+      match statistic {
+         Statistic::Mean => self.mean,
+         Statistic::Min => self.min,
+         Statistic::Max => self.max,
+         Statistic::P50 => self.p50,
+         Statistic::P95 => self.p95,
+         Statistic::P99 => self.p99,
+         Statistic::StdDev => self.std_dev,
+         Statistic::MeanPerCall => self.mean_per_call,
+         Statistic::SelfTime => self.self_time,
+      }
+   }
+}
+
+
+/// a single value out of ``FunctionStats``, picks what the graph plots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Statistic {
+   #[default]
+   Mean,
+   Min,
+   Max,
+   P50,
+   P95,
+   P99,
+   StdDev,
+   MeanPerCall,
+   SelfTime,
+}
+impl Statistic {
+   pub const ALL: [Statistic; 9] = [
+      Statistic::Mean,
+      Statistic::Min,
+      Statistic::Max,
+      Statistic::P50,
+      Statistic::P95,
+      Statistic::P99,
+      Statistic::StdDev,
+      Statistic::MeanPerCall,
+      Statistic::SelfTime,
+   ];
+
+   pub fn label(&self) -> &'static str {
+      match self {
+         Statistic::Mean => "Mean",
+         Statistic::Min => "Min",
+         Statistic::Max => "Max",
+         Statistic::P50 => "P50",
+         Statistic::P95 => "P95",
+         Statistic::P99 => "P99",
+         Statistic::StdDev => "Std dev",
+         Statistic::MeanPerCall => "Mean per call",
+         Statistic::SelfTime => "Self time",
+      }
+   }
+}
+
+/// current time in ms since the profiler started
+pub(crate) fn get_ct() -> f64 {
    ST.elapsed().as_secs_f64() * 1000.0
-}
\ No newline at end of file
+}
+
+/// ``get_ct`` of an ``Instant`` taken earlier, ``ST`` is set when the first profiler is created so it's never later
+#[cfg(not(feature = "disabled"))]
+pub(crate) fn ms_at(at: Instant) -> f64 {
+   at.saturating_duration_since(*ST).as_secs_f64() * 1000.0
+}
@@ -0,0 +1,169 @@
+#[cfg(not(feature = "disabled"))]
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+#[cfg(not(feature = "disabled"))]
+use std::sync::{Mutex, PoisonError};
+use std::sync::RwLock;
+use std::task::{Context, Poll};
+
+#[cfg(not(feature = "disabled"))]
+use lazy_static::lazy_static;
+use pin_project_lite::pin_project;
+
+#[cfg(not(feature = "disabled"))]
+use crate::event_buffer::{record, record_elapsed, EventKind};
+#[cfg(not(feature = "disabled"))]
+use crate::function_profile::get_ct;
+use crate::{PerformanceProfiler, StatString};
+
+
+#[cfg(not(feature = "disabled"))]
+pin_project! {
+   /// a future timed as it runs, a future can hop threads and interleave with other tasks between polls
+   /// so it's never part of a function tree, instead it adds two general functions to the thread that polls it,
+   /// ``NAME`` is the time spent inside ``poll`` and ``NAME (wall)`` the time from the first poll until it
+   /// finished, awaiting included, created with ``ProfileFuture::profile`` or ``#[time_event]`` on an ``async fn``
+   /// ```
+   /// use triglyceride::{init_profiler, ProfileFuture, Settings};
+   ///
+   /// init_profiler!(PROF, Settings::default());
+   ///
+   /// async fn load() -> usize {
+   ///    42
+   /// }
+   ///
+   /// async fn loader() -> usize {
+   ///    load().profile(&PROF, "LOAD").await
+   /// }
+   /// ```
+   #[must_use = "futures do nothing unless polled"]
+   pub struct Instrumented<F> {
+      #[pin]
+      future: F,
+      profiler: &'static RwLock<PerformanceProfiler>,
+      name: StatString,
+
+      // time of the first poll in ms, ``None`` before it
+      first_poll: Option<f64>,
+      poll_ms: f64,
+      wall_ms: f64,
+      finished: bool,
+   }
+}
+
+#[cfg(feature = "disabled")]
+pin_project! {
+   /// the ``disabled`` feature polls the future and nothing else
+   #[must_use = "futures do nothing unless polled"]
+   pub struct Instrumented<F> {
+      #[pin]
+      future: F,
+   }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<F: Future> Instrumented<F> {
+   pub fn new(future: F, profiler: &'static RwLock<PerformanceProfiler>, name: StatString) -> Self {
+      Self {
+         future,
+         profiler,
+         name,
+         first_poll: None,
+         poll_ms: 0.0,
+         wall_ms: 0.0,
+         finished: false,
+      }
+   }
+
+   /// ms spent inside ``poll`` so far
+   pub fn poll_time(&self) -> f64 {
+      self.poll_ms
+   }
+
+   /// ms since the first poll, or until it finished once it has
+   pub fn wall_time(&self) -> f64 {
+      match self.first_poll {
+         Some(first_poll) if !self.finished => get_ct() - first_poll,
+         _ => self.wall_ms,
+      }
+   }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<F: Future> Future for Instrumented<F> {
+   type Output = F::Output;
+
+   fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+      let this = self.project();
+
+      let start = get_ct();
+      let first_poll = *this.first_poll.get_or_insert(start);
+
+      record(this.profiler, this.name, EventKind::LoneStart);
+      let result = this.future.poll(cx);
+      record(this.profiler, this.name, EventKind::LoneEnd);
+
+      let end = get_ct();
+      *this.poll_ms += end - start;
+
+      if result.is_ready() {
+         *this.wall_ms = end - first_poll;
+         *this.finished = true;
+         record_elapsed(this.profiler, wall_name(this.name), *this.wall_ms);
+      }
+
+      result
+   }
+}
+
+#[cfg(feature = "disabled")]
+impl<F: Future> Instrumented<F> {
+   #[inline(always)]
+   pub fn new(future: F, _profiler: &'static RwLock<PerformanceProfiler>, _name: StatString) -> Self {
+      Self { future }
+   }
+
+   /// always 0, nothing is timed
+   pub fn poll_time(&self) -> f64 {
+      0.0
+   }
+
+   /// always 0, nothing is timed
+   pub fn wall_time(&self) -> f64 {
+      0.0
+   }
+}
+
+#[cfg(feature = "disabled")]
+impl<F: Future> Future for Instrumented<F> {
+   type Output = F::Output;
+
+   #[inline(always)]
+   fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+      self.project().future.poll(cx)
+   }
+}
+
+
+/// ``fut.profile(&PROF, "NAME")`` for every future
+pub trait ProfileFuture: Future + Sized {
+   /// times the future as it runs, see ``Instrumented``
+   #[inline(always)]
+   fn profile(self, profiler: &'static RwLock<PerformanceProfiler>, name: StatString) -> Instrumented<Self> {
+      Instrumented::new(self, profiler, name)
+   }
+}
+impl<F: Future> ProfileFuture for F {}
+
+
+/// ``NAME (wall)``, leaked once per name
+#[cfg(not(feature = "disabled"))]
+fn wall_name(name: StatString) -> StatString {
+   lazy_static! {
+      static ref WALL_NAMES: Mutex<HashMap<StatString, StatString>> = Mutex::default();
+   }
+
+   let mut names = WALL_NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+   names.entry(name).or_insert_with(|| Box::leak(format!("{name} (wall)").into_boxed_str()))
+}
@@ -1,34 +1,76 @@
-use std::sync::{RwLock, RwLockWriteGuard};
+use std::sync::{PoisonError, RwLock, RwLockWriteGuard};
 
 pub use lazy_static::lazy_static;
 
-pub use function_profile::FunctionProfile;
+pub use captured_frame::{CapturedFrame, Span};
+pub use compare::FunctionDiff;
+pub use function_profile::{FunctionProfile, FunctionStats, Statistic};
+pub use instrumented::{Instrumented, ProfileFuture};
+pub use overhead::Overhead;
 pub use profiler::{PerformanceProfiler, Settings};
-pub use tri_macros::time_event;
+pub use profiler_error::ProfilerError;
+#[cfg(unix)]
+pub use remote::connect_remote_unix;
+pub use remote::{connect_remote, follow_remote, ProfilerServer};
+pub use report::HeadlessReporter;
+pub use scope_guard::{lone_scope, scope, ScopeGuard};
+pub use session::SESSION_VERSION;
+pub use thread_profile::{LaneId, ThreadProfile};
+pub use utils::tree::{CallPaths, PathId, Tree, TreeNode};
+pub use tri_macros::{time_event, time_lone_function};
+#[doc(hidden)]
+pub use utils::names::method_event_name;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub use ui::disconnected_display_window::spawn_disconnected_window;
+#[cfg(all(feature = "window", not(target_arch = "wasm32")))]
+pub use ui::disconnected_display_window::{run_window, spawn_disconnected_session_window, spawn_disconnected_window};
 
+pub(crate) mod captured_frame;
+pub(crate) mod compare;
+#[cfg(not(feature = "disabled"))]
+pub(crate) mod event_buffer;
 pub(crate) mod function_profile;
+pub(crate) mod instrumented;
+pub(crate) mod overhead;
 pub(crate) mod profiler;
+pub(crate) mod profiler_error;
+pub(crate) mod remote;
+pub(crate) mod report;
+pub(crate) mod scope_guard;
+pub(crate) mod session;
+pub(crate) mod thread_profile;
 pub(crate) mod utils {
    pub(crate) mod tree;
    pub(crate) mod macros;
+   #[cfg(feature = "ui")]
    pub(crate) mod ui_modules;
+   pub(crate) mod json;
+   pub(crate) mod names;
 }
+pub(crate) mod export {
+   pub(crate) mod chrome_trace;
+   pub(crate) mod folded_stacks;
+   pub(crate) mod speedscope;
+}
+#[cfg(feature = "ui")]
 pub mod ui {
    pub mod user_interface;
 
    pub mod new_display;
+
+   pub mod frame_history;
+
+   pub mod flame_chart;
    
-   #[cfg(not(target_arch = "wasm32"))]
+   #[cfg(all(feature = "window", not(target_arch = "wasm32")))]
    pub(crate) mod disconnected_display_window;
 }
 
 pub type StatString = &'static str;
 
 
-/// used to access a profiler static, takes a closure to a mut profiler 
+/// used to access a profiler static, takes a closure to a mut profiler and returns whatever it returns,
+/// a lock poisoned by a panic elsewhere is recovered instead of propagating the panic,
+/// events buffered by ``scope`` and the macros since the last open are applied first
 /// ```
 /// use triglyceride::{init_profiler, open_profiler, Settings};
 /// 
@@ -47,11 +89,24 @@ pub type StatString = &'static str;
 /// 
 /// ```
 #[inline(always)]
-pub fn open_profiler<F>(profiler: &'static RwLock<PerformanceProfiler>, code: F)
+pub fn open_profiler<F, R>(profiler: &'static RwLock<PerformanceProfiler>, code: F) -> R
 where
-    F: FnOnce(RwLockWriteGuard<'static, PerformanceProfiler>),
+    F: FnOnce(RwLockWriteGuard<'static, PerformanceProfiler>) -> R,
 {
-   code(profiler.write().unwrap());
+   // direct calls would otherwise be applied before the scopes still waiting in this threads buffer
+   #[cfg(not(feature = "disabled"))]
+   event_buffer::flush_current_thread(profiler);
+
+   let start = function_profile::get_ct();
+   let mut profiler = profiler.write().unwrap_or_else(PoisonError::into_inner);
+   let lock_wait = function_profile::get_ct() - start;
+
+   #[cfg(not(feature = "disabled"))]
+   profiler.apply_pending();
+   profiler.resolve_lone_lanes();
+   profiler.track_overhead(lock_wait);
+
+   code(profiler)
 }
 
 /// used to modify a profilers settings though code instead of the ui
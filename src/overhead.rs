@@ -0,0 +1,92 @@
+use crate::function_profile::get_ct;
+use crate::PerformanceProfiler;
+
+
+/// time the profiler spent on itself, in ms per second of wall time
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Overhead {
+   /// waiting for the profilers lock, in ``open_profiler`` and when a thread hands over its events
+   pub lock_wait: f64,
+
+   /// applying buffered events, timing spans and building the function trees
+   pub events: f64,
+
+   /// ``resolve_profiler``, the statistics of every interval
+   pub resolve: f64,
+
+   /// drawing the ui
+   pub ui: f64,
+}
+impl Overhead {
+   pub fn total(&self) -> f64 {
+      self.lock_wait + self.events + self.resolve + self.ui
+   }
+
+   /// share of wall time in percent
+   pub fn percent(&self) -> f64 {
+      self.total() / 10.0
+   }
+
+   fn scaled(&self, factor: f64) -> Self {
+      Self {
+         lock_wait: self.lock_wait * factor,
+         events: self.events * factor,
+         resolve: self.resolve * factor,
+         ui: self.ui * factor,
+      }
+   }
+}
+
+
+/// sums ``Overhead`` and turns it into a rate about once a second
+#[derive(Debug)]
+pub(crate) struct OverheadTracker {
+   pub(crate) current: Overhead,
+   latest: Overhead,
+   since: f64,
+}
+impl Default for OverheadTracker {
+   fn default() -> Self {
+      Self {
+         current: Overhead::default(),
+         latest: Overhead::default(),
+         since: get_ct(),
+      }
+   }
+}
+impl OverheadTracker {
+   fn roll(&mut self) {
+      let now = get_ct();
+      let elapsed = now - self.since;
+      if elapsed < ROLL_MS { return; }
+
+      self.latest = self.current.scaled(1000.0 / elapsed);
+      self.current = Overhead::default();
+      self.since = now;
+   }
+}
+
+const ROLL_MS: f64 = 1000.0;
+
+
+/// keeping track of the profilers own cost, so settings that distort the numbers show up
+impl PerformanceProfiler {
+   /// what the profiler cost over the last second
+   pub fn overhead(&self) -> Overhead {
+      self.overhead.latest
+   }
+
+   /// internal function, called every time the profiler is opened
+   pub(crate) fn track_overhead(&mut self, lock_wait: f64) {
+      self.overhead.current.lock_wait += lock_wait;
+      self.overhead.current.resolve += self.take_resolve_time();
+      self.overhead.roll();
+   }
+
+   /// internal function, time the lanes spent resolving since the last call
+   pub(crate) fn take_resolve_time(&mut self) -> f64 {
+      self.threads.iter_mut()
+          .map(|lane| std::mem::take(&mut lane.resolve_ms))
+          .sum()
+   }
+}
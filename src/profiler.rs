@@ -1,11 +1,19 @@
-use std::collections::HashMap;
-
-use instant::Instant;
-
-use crate::function_profile::FunctionProfile;
+use std::sync::RwLock;
+#[cfg(not(feature = "disabled"))]
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, Thread, ThreadId};
+
+#[cfg(not(feature = "disabled"))]
+use crate::event_buffer::{Event, EventKind, Pending};
+use crate::function_profile::get_ct;
+#[cfg(not(feature = "disabled"))]
+use crate::function_profile::ms_at;
+use crate::overhead::OverheadTracker;
+use crate::profiler_error::ProfilerError;
 use crate::StatString;
+use crate::thread_profile::{LaneId, ThreadProfile};
+#[cfg(feature = "ui")]
 use crate::ui::user_interface::UiData;
-use crate::utils::tree::Tree;
 
 use lazy_bastard::lazy_bastard;
 
@@ -18,6 +26,9 @@ lazy_bastard!(
       pub stored_cash_amount: u32 => 20,
       pub update_interval_sec: f64 => 0.5,
       pub smoothing_amount: u32 => 5,
+      pub stored_frame_amount: u32 => 120,
+      pub paused: bool => false,
+      pub spike_threshold_ms: f64 => 0.0,
    }
 );
 
@@ -26,241 +37,214 @@ lazy_bastard!(
 /// with ``open_profiler(&PROF, |mut p| {})``
 #[derive(Debug)]
 pub struct PerformanceProfiler {
-   /// all timed functions ``HashMap<function_name, profile>``
-   pub all_profiles: HashMap<StatString, FunctionProfile>,
+   /// one lane per thread that has timed something, in order of first use
+   pub threads: Vec<ThreadProfile>,
 
    /// profiler settings
    pub settings: Settings,
 
-   /// latest computed function tree
-   pub latest_tree: Tree,
-
-   /// queues a tree processes for the next iteration
-   pub queue_processes_tree: bool,
-
    /// self-explanatory
+   #[cfg(feature = "ui")]
    pub ui_data: UiData,
 
-   pub(crate) is_actually_active_or_not: bool,
-   pub(crate) last_dump: Instant,
-   pub(crate) processioning_tree: bool,
-   pub(crate) active_tree: Tree,
-   pub(crate) traverser: Vec<StatString>,
-   ticks_since_start: u32,
-   ticks_since_last_dump: u32,
+   /// earlier data to compare against, a loaded session or a snapshot of this profiler
+   pub baseline: Option<Box<PerformanceProfiler>>,
 
-   inner_constant_reference: Option<StatString>,
+   /// problems not tied to a thread, like a broken remote connection, shown with the threads warnings
+   pub warnings: Vec<String>,
 
-   /// first start event
-   outermost_upper: Option<StatString>,
+   /// frames of events handed over by threads, applied the next time the profiler is opened
+   #[cfg(not(feature = "disabled"))]
+   pub(crate) pending: Mutex<Pending>,
 
-   /// last end event
-   outermost_lower: Option<StatString>,
+   /// time spent on the profiler itself, read with ``overhead``
+   pub(crate) overhead: OverheadTracker,
 }
 impl PerformanceProfiler {
    pub fn new(settings: Settings) -> Self {
       Self {
-         all_profiles: Default::default(),
+         threads: vec![],
          settings,
-
-         is_actually_active_or_not: true,
-         latest_tree: Default::default(),
-         last_dump: Instant::now(),
-         queue_processes_tree: false,
-         processioning_tree: false,
-         active_tree: Default::default(),
-         traverser: vec![],
-
-         ticks_since_start: 0,
-         ticks_since_last_dump: 0,
-         inner_constant_reference: None,
-         outermost_upper: None,
+         #[cfg(feature = "ui")]
          ui_data: UiData::default(),
-         outermost_lower: None,
+         baseline: None,
+         warnings: vec![],
+         #[cfg(not(feature = "disabled"))]
+         pending: Mutex::default(),
+         overhead: OverheadTracker::default(),
       }
    }
 
-   /// starts a profiler for a general function, use event loop variant for a function tree
-   pub fn start_time_function(&mut self, name: StatString) {
-      if !self.is_actually_active_or_not { return; }
+   /// a profiler made at runtime for something that outlives the caller, like a window or a reading thread,
+   /// it's leaked, a viewer only ever opens a handful of them
+   pub fn into_static(self) -> &'static RwLock<Self> {
+      Box::leak(Box::new(RwLock::new(self)))
+   }
 
-      match self.all_profiles.get_mut(name) {
-         None => {
-            self.all_profiles.insert(name, FunctionProfile::default());
-            self.start_time_function(name);
-         }
-         Some(profile) => {
-            profile.start();
-         }
-      }
+   /// the lane of a specific thread, if it has timed anything yet
+   pub fn thread(&self, id: ThreadId) -> Option<&ThreadProfile> {
+      self.threads.iter().find(|t| t.thread_id == Some(id))
    }
 
+   /// a lane by its id, live or loaded
+   pub fn lane(&self, id: LaneId) -> Option<&ThreadProfile> {
+      self.threads.iter().find(|t| t.id == id)
+   }
 
-   /// ends a profiler for a general function, use event loop variant for a function tree
-   pub fn end_time_function(&mut self, name: StatString) -> Result<(), ()> {
-      if !self.is_actually_active_or_not { return Ok(()); }
+   /// the lane of the calling thread, created on first use
+   pub fn current_thread(&mut self) -> &mut ThreadProfile {
+      self.current_thread_and_settings().0
+   }
 
-      match self.all_profiles.get_mut(name) {
+   /// internal function, splits the borrow so lanes can read the settings
+   fn current_thread_and_settings(&mut self) -> (&mut ThreadProfile, &Settings) {
+      let index = self.lane_index(&thread::current());
+      (&mut self.threads[index], &self.settings)
+   }
+
+   /// internal function, index of a threads lane, created on first use
+   fn lane_index(&mut self, thread: &Thread) -> usize {
+      match self.threads.iter().position(|t| t.thread_id == Some(thread.id())) {
+         Some(index) => index,
          None => {
-            self.all_profiles.insert(name, FunctionProfile::default());
-            self.all_profiles.get_mut(name).unwrap().start();
-            Ok(())
-         }
-         Some(profile) => {
-            profile.end();
-            Ok(())
+            self.threads.push(ThreadProfile::for_thread(thread));
+            self.threads.len() - 1
          }
       }
    }
 
-   /// internal function
-   fn at_outermost_upper(&mut self) {
-      self.resolve_profiler(true);
-
-      // start processes if queued
-      if self.queue_processes_tree {
-         self.processioning_tree = true;
-         self.queue_processes_tree = false;
-      }
+   /// starts a profiler for a general function, use event loop variant for a function tree
+   pub fn start_time_function(&mut self, name: StatString) {
+      self.current_thread().start_time_function(name);
+   }
 
-      let (upper, lower) = (self.outermost_upper.unwrap(), self.outermost_lower.unwrap());
+   /// ends a profiler for a general function, use event loop variant for a function tree
+   pub fn end_time_function(&mut self, name: StatString) -> Result<(), ProfilerError> {
+      self.current_thread().end_time_function(name)
+   }
 
-      match self.inner_constant_reference {
-         None => {
-            if upper != lower {
-               panic!(
-                  "The loop and no overarching function,\n
-                   if you cannot encase the entire event loop in one function use set_constant_reference()\n
-                     Upper = {:?} | Lower = {:?}
-                   ", upper, lower
-               )
-            } // invalid check
-
-            // start tree
-            if self.processioning_tree {
-               self.latest_tree = std::mem::take(&mut self.active_tree);
-               self.active_tree.clear();
-               self.traverser.clear();
-
-               self.active_tree.set_root(upper);
-               self.traverser.push(upper);
-            }
-         }
+   /// starts profiling an inner event function on the calling threads tree,
+   /// errors are also kept in ``ThreadProfile::warnings`` for the ui
+   /// ```
+   /// use std::thread::sleep;
+   /// use std::time::Duration;
+   /// use triglyceride::{PerformanceProfiler, Settings};
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings { spike_threshold_ms: 5.0, ..Settings::default() });
+   ///
+   /// for sleep_ms in [0, 0, 8, 0, 0] {
+   ///    profiler.time_event_start("FRAME").unwrap();
+   ///    sleep(Duration::from_millis(sleep_ms));
+   ///    profiler.time_event_end("FRAME").unwrap();
+   /// }
+   ///
+   /// // paused as the spike ended, the frames after it were never stored
+   /// assert!(profiler.settings.paused);
+   /// let lane = profiler.current_thread();
+   /// assert_eq!(lane.frames.len(), 3);
+   /// assert!(lane.frames.back().unwrap().duration() >= 8.0);
+   /// ```
+   pub fn time_event_start(&mut self, name: StatString) -> Result<(), ProfilerError> {
+      let (lane, settings) = self.current_thread_and_settings();
+      let result = lane.time_event_start(settings, name);
+
+      let index = self.lane_index(&thread::current());
+      self.freeze_on_spike(index);
+      result
+   }
 
-         Some(reference) => {
-            if self.processioning_tree {
-               self.latest_tree = std::mem::take(&mut self.active_tree);
-               self.active_tree.clear();
-               self.traverser.clear();
+   /// internal function, pauses the profiler and shows the offending frame once a frame of a
+   /// lane goes over ``Settings::spike_threshold_ms``
+   fn freeze_on_spike(&mut self, index: usize) {
+      let lane = &mut self.threads[index];
+      let Some(number) = lane.spike.take() else { return; };
+      let frame = lane.frame(number).cloned().map(|frame| (lane.id, frame));
 
-               self.active_tree.set_root(reference);
-               self.traverser.push(reference);
+      self.settings.paused = true;
 
-               let parent = self.traverser.last().unwrap();
-               self.active_tree.add_child(parent, upper);
-               self.traverser.push(upper);
-            }
-         }
+      #[cfg(feature = "ui")]
+      if frame.is_some() {
+         self.ui_data.selected_frame = frame;
       }
+      #[cfg(not(feature = "ui"))]
+      let _ = frame;
    }
 
-   /// starts profiling an inner event function
-   pub fn time_event_start(&mut self, name: StatString) {
-      match self.outermost_upper {
-         None => {
-            self.outermost_upper = Some(name);
-         }
-
-         Some(outer) => {
-            if outer == name {
-               self.at_outermost_upper();
-            }
-
-            // not outermost loop
-            else {
-               if self.processioning_tree {
-                  let parent = self.traverser.last().unwrap();
-                  self.active_tree.add_child(parent, name);
-                  self.traverser.push(name);
-               }
-            }
-         }
-      }
+   /// ends profiling an inner event function on the calling threads tree,
+   /// errors are also kept in ``ThreadProfile::warnings`` for the ui
+   pub fn time_event_end(&mut self, name: StatString) -> Result<(), ProfilerError> {
+      self.current_thread().time_event_end(name)
+   }
 
-      self.start_time_function(name);
+   /// sets a reference that is called every frame instead of an overarching function to start the tree,
+   /// only applies to the calling thread
+   pub fn set_constant_reference(&mut self, name: StatString) {
+      self.current_thread().set_constant_reference(name);
    }
 
-   /// ends profiling an inner event function
-   pub fn time_event_end(&mut self, name: StatString) {
-      self.end_time_function(name).unwrap();
+   /// internal function, applies every frame of events handed over since the last call
+   #[cfg(not(feature = "disabled"))]
+   pub(crate) fn apply_pending(&mut self) {
+      let pending = self.pending.get_mut().unwrap_or_else(PoisonError::into_inner);
+      if pending.batches.is_empty() { return; }
 
-      // is checked in outermost upper
-      self.outermost_lower = Some(name);
+      let (batches, lock_wait) = pending.take();
 
-      // function tree
-      if self.processioning_tree {
-         self.traverser.pop();
+      let start = get_ct();
+      self.overhead.current.resolve += self.take_resolve_time();
+      for batch in batches.iter() {
+         self.replay(&batch.thread, &batch.events);
       }
+
+      // frames ending while replaying resolve the lanes, that's counted on its own
+      let resolve = self.take_resolve_time();
+      self.overhead.current.resolve += resolve;
+      self.overhead.current.events += get_ct() - start - resolve;
+      self.overhead.current.lock_wait += lock_wait;
    }
 
-   /// sets a reference that is called every frame instead of an overarching function to start the tree
-   // TODO hacky
-   pub fn set_constant_reference(&mut self, name: StatString) {
-      match self.inner_constant_reference {
-         None => {
-            self.inner_constant_reference = Some(name);
-         }
-         Some(_) => {
-            self.end_time_function(name).unwrap();
-         }
+   /// internal function, applies a threads buffered events in the order they happened,
+   /// errors end up in ``ThreadProfile::warnings`` the same as unbuffered calls
+   #[cfg(not(feature = "disabled"))]
+   pub(crate) fn replay(&mut self, thread: &Thread, events: &[Event]) {
+      let index = self.lane_index(thread);
+
+      for event in events {
+         let lane = &mut self.threads[index];
+         let now = ms_at(event.at);
+         let _ = match event.kind {
+            EventKind::Start => {
+               let result = lane.time_event_start_at(&self.settings, event.name, now);
+               self.freeze_on_spike(index);
+               result
+            }
+            EventKind::End => lane.time_event_end_at(event.name, now),
+            EventKind::LoneStart => {
+               lane.start_time_function_at(event.name, now);
+               Ok(())
+            }
+            EventKind::LoneEnd => lane.end_time_function_at(event.name, now),
+            EventKind::Elapsed(elapsed_ms) => {
+               lane.add_function_call(event.name, elapsed_ms);
+               Ok(())
+            }
+         };
       }
-
-      self.start_time_function(name);
    }
 
-   
-   /// calculate averages, only runs every ``Settings::update_interval``
+   /// calculate averages for the calling thread, only runs every ``Settings::update_interval``
    pub fn resolve_profiler(&mut self, queue_tree: bool) {
-      if self.is_actually_active_or_not != self.settings.active {
-         if self.settings.active == false {
-            self.inner_resolve(queue_tree);
-         }
-         self.is_actually_active_or_not = self.settings.active;
-      }
-
-      if self.is_actually_active_or_not { self.inner_resolve(queue_tree); }
+      let (lane, settings) = self.current_thread_and_settings();
+      lane.resolve_profiler(settings, queue_tree);
    }
 
-   /// internal function
-   fn inner_resolve(&mut self, queue_tree: bool) {
-      self.ticks_since_start += 1;
-      self.ticks_since_last_dump += 1;
-
-      if (self.last_dump.elapsed().as_secs_f64() > self.settings.update_interval_sec) && self.ticks_since_last_dump > 3 {
-         self.ticks_since_last_dump = 0;
-         self.last_dump = Instant::now();
-
-         for (name, profile) in self.all_profiles.iter_mut() {
-            match self.inner_constant_reference {
-               None => { profile.resolve(self.settings.stored_cash_amount, self.settings.stored_data_amount, false, self.ticks_since_start); }
-               Some(inner) => {
-                  if *name == inner {
-                     profile.resolve(self.settings.stored_cash_amount, self.settings.stored_data_amount, true, self.ticks_since_start);
-                  } else {
-                     profile.resolve(self.settings.stored_cash_amount, self.settings.stored_data_amount, false, self.ticks_since_start);
-                  }
-               }
-            }
-
-         }
-
-         self.queue_processes_tree = queue_tree;
+   /// internal function, resolves the lanes that only time general functions, nothing else would,
+   /// called every time the profiler is opened and still only runs every ``Settings::update_interval``,
+   /// loaded lanes are left alone, they have nothing new and would only push empty intervals
+   pub(crate) fn resolve_lone_lanes(&mut self) {
+      let now = get_ct();
+      for lane in self.threads.iter_mut().filter(|lane| lane.thread_id.is_some() && !lane.has_tree()) {
+         lane.resolve_profiler_at(&self.settings, false, now);
       }
    }
-   
 }
-
-
-
-
-
@@ -0,0 +1,86 @@
+use std::fmt::{Display, Formatter};
+
+use crate::StatString;
+
+
+/// mistakes in instrumentation, reported instead of panicking and shown as a warning banner in the ui
+/// ```
+/// use triglyceride::{ProfilerError::*, Settings, ThreadProfile};
+///
+/// // resolving every frame, the tree is built from the fifth one on
+/// let settings = Settings { update_interval_sec: 0.0, ..Settings::default() };
+/// let mut lane = ThreadProfile::new(None, "main".to_string());
+///
+/// lane.time_event_start(&settings, "FRAME").unwrap();
+/// assert_eq!(lane.time_event_start(&settings, "FRAME"), Err(MissingRoot { name: "FRAME" }));
+/// assert_eq!(lane.time_event_end("NEVER"), Err(UnbalancedEnd { name: "NEVER", expected: None }));
+///
+/// for _ in 0..6 {
+///    lane.time_event_end("FRAME").unwrap();
+///    lane.time_event_start(&settings, "FRAME").unwrap();
+/// }
+///
+/// lane.time_event_start(&settings, "OUTER").unwrap();
+/// lane.time_event_start(&settings, "INNER").unwrap();
+/// assert_eq!(lane.time_event_end("OUTER"), Err(UnbalancedEnd { name: "OUTER", expected: Some("INNER") }));
+///
+/// lane.time_event_end("FRAME").unwrap();
+/// assert_eq!(lane.time_event_start(&settings, "STRAY"), Err(UnknownParent { name: "STRAY" }));
+/// let _ = lane.time_event_end("STRAY");
+/// assert_eq!(lane.time_event_start(&settings, "FRAME"), Err(MismatchedNesting { upper: "FRAME", lower: "STRAY" }));
+///
+/// // each distinct error is kept once for the warning banner
+/// assert_eq!(lane.warnings.len(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfilerError {
+   /// the first start event and the last end event of a loop differ and no constant reference is set
+   MismatchedNesting {
+      upper: StatString,
+      lower: StatString,
+   },
+
+   /// an event was started while its parent isn't on the event stack
+   UnknownParent {
+      name: StatString,
+   },
+
+   /// an event was ended that isn't the innermost open event, or was never started
+   UnbalancedEnd {
+      name: StatString,
+      expected: Option<StatString>,
+   },
+
+   /// the loop restarted without any event ending, so there is nothing to root the tree on
+   MissingRoot {
+      name: StatString,
+   },
+}
+impl Display for ProfilerError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         ProfilerError::MismatchedNesting { upper, lower } => write!(
+            f,
+            "the loop has no overarching function (upper = {upper:?} | lower = {lower:?}), \
+             if you cannot encase the entire event loop in one function use set_constant_reference()"
+         ),
+         ProfilerError::UnknownParent { name } => write!(
+            f,
+            "{name:?} was started with no open parent event"
+         ),
+         ProfilerError::UnbalancedEnd { name, expected: Some(expected) } => write!(
+            f,
+            "{name:?} was ended while {expected:?} was still open"
+         ),
+         ProfilerError::UnbalancedEnd { name, expected: None } => write!(
+            f,
+            "{name:?} was ended without being started"
+         ),
+         ProfilerError::MissingRoot { name } => write!(
+            f,
+            "{name:?} restarted the loop before any event ended, no root for the tree"
+         ),
+      }
+   }
+}
+impl std::error::Error for ProfilerError {}
@@ -0,0 +1,320 @@
+//! streaming a running profiler to a viewer in another process, so the profiled app needs no window
+//! or egui of its own, see ``ProfilerServer`` and ``connect_remote``
+//!
+//! the wire format is the session format (see ``PerformanceProfiler::write_session``) sent once per
+//! ``Settings::update_interval_sec``, every session is followed by a line holding only ``end``
+//!
+//! ```text
+//! triglyceride-session  <version>
+//! setting   ...
+//! thread    ...
+//! ...
+//! end
+//! triglyceride-session  <version>
+//! ...
+//! end
+//! ```
+//! a session never has a line that is just ``end``, every record starts with its kind and text is escaped,
+//! each session is complete on its own so a viewer can connect at any time
+
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::RwLock;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::{open_profiler, PerformanceProfiler, Settings};
+
+
+/// ends every session on the wire
+const END: &str = "end";
+
+/// how often a waiting server checks for new viewers
+const ACCEPT_SLEEP_SEC: f64 = 0.05;
+
+/// a viewer that stops reading is dropped after this instead of stalling every other viewer
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+
+/// sends a session to every connected viewer every ``Settings::update_interval_sec``,
+/// the profiler is only locked while the session is written to memory, a slow viewer never holds it
+/// ```no_run
+/// use triglyceride::{init_profiler, ProfilerServer, Settings};
+///
+/// init_profiler!(PROF, Settings::default());
+///
+/// fn main() -> std::io::Result<()> {
+///    ProfilerServer::bind(&PROF, "127.0.0.1:7878")?.spawn();
+///
+///    // run the app, then ``triglyceride-viewer 127.0.0.1:7878``
+///    Ok(())
+/// }
+/// ```
+pub struct ProfilerServer {
+   prof: &'static RwLock<PerformanceProfiler>,
+   listener: Listener,
+   viewers: Vec<Box<dyn Write + Send>>,
+   last_send: Instant,
+}
+impl ProfilerServer {
+   /// listens for viewers on a tcp address, port 0 picks a free port, see ``local_addr``
+   pub fn bind(prof: &'static RwLock<PerformanceProfiler>, addr: impl ToSocketAddrs) -> io::Result<Self> {
+      let listener = TcpListener::bind(addr)?;
+      listener.set_nonblocking(true)?;
+
+      Ok(Self::new(prof, Listener::Tcp(listener)))
+   }
+
+   /// listens for viewers on a unix socket, an old socket file at ``path`` is replaced
+   #[cfg(unix)]
+   pub fn bind_unix(prof: &'static RwLock<PerformanceProfiler>, path: impl AsRef<Path>) -> io::Result<Self> {
+      let _ = std::fs::remove_file(&path);
+      let listener = UnixListener::bind(path)?;
+      listener.set_nonblocking(true)?;
+
+      Ok(Self::new(prof, Listener::Unix(listener)))
+   }
+
+   fn new(prof: &'static RwLock<PerformanceProfiler>, listener: Listener) -> Self {
+      Self {
+         prof,
+         listener,
+         viewers: vec![],
+         last_send: Instant::now(),
+      }
+   }
+
+   /// the bound tcp address, ``None`` for unix sockets
+   pub fn local_addr(&self) -> Option<std::net::SocketAddr> {
+      match &self.listener {
+         Listener::Tcp(listener) => listener.local_addr().ok(),
+         #[cfg(unix)]
+         Listener::Unix(_) => None,
+      }
+   }
+
+   /// viewers currently connected
+   pub fn viewers(&self) -> usize {
+      self.viewers.len()
+   }
+
+   /// accepts new viewers and sends if ``Settings::update_interval_sec`` passed since the last send
+   pub fn poll(&mut self) {
+      self.accept();
+
+      let interval = open_profiler(self.prof, |p| p.settings.update_interval_sec);
+      if self.last_send.elapsed().as_secs_f64() < interval { return; }
+
+      self.send_now();
+   }
+
+   /// sends a session to every viewer straight away, viewers that went away are dropped
+   pub fn send_now(&mut self) {
+      self.last_send = Instant::now();
+      if self.viewers.is_empty() { return; }
+
+      let mut session = vec![];
+      if open_profiler(self.prof, |p| p.write_session(&mut session)).is_err() { return; }
+      session.extend_from_slice(END.as_bytes());
+      session.push(b'\n');
+
+      self.viewers.retain_mut(|viewer| viewer.write_all(&session).and_then(|_| viewer.flush()).is_ok());
+   }
+
+   /// serves from a thread of its own for as long as the program runs
+   pub fn spawn(mut self) -> JoinHandle<()> {
+      thread::Builder::new()
+          .name("ProfilerServerThread".to_string())
+          .spawn(move || loop {
+             self.poll();
+             thread::sleep(Duration::from_secs_f64(ACCEPT_SLEEP_SEC));
+          })
+          .expect("failed to spawn server thread")
+   }
+
+   fn accept(&mut self) {
+      loop {
+         let viewer: io::Result<Box<dyn Write + Send>> = match &self.listener {
+            Listener::Tcp(listener) => listener.accept().and_then(|(stream, _)| {
+               stream.set_nonblocking(false)?;
+               stream.set_nodelay(true)?;
+               stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+               Ok(Box::new(stream) as Box<dyn Write + Send>)
+            }),
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.accept().and_then(|(stream, _)| {
+               stream.set_nonblocking(false)?;
+               stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+               Ok(Box::new(stream) as Box<dyn Write + Send>)
+            }),
+         };
+
+         match viewer {
+            Ok(viewer) => self.viewers.push(viewer),
+
+            // WouldBlock once everyone waiting is accepted, other errors are a viewer that gave up
+            Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+            Err(_) => continue,
+         }
+      }
+   }
+}
+
+enum Listener {
+   Tcp(TcpListener),
+   #[cfg(unix)]
+   Unix(UnixListener),
+}
+
+
+/// a profiler kept up to date with a ``ProfilerServer`` over tcp, for the ui of a viewer,
+/// it keeps the last session it got once the app exits
+/// ```
+/// use std::thread::sleep;
+/// use std::time::Duration;
+/// use triglyceride::{change_profiler_settings, connect_remote, open_profiler, FunctionProfile, FunctionStats, PerformanceProfiler, ProfilerServer, Settings, ThreadProfile};
+///
+/// let mut lane = ThreadProfile::new(None, "main".to_string());
+/// let frame = lane.call_paths.intern(None, "FRAME");
+/// let mut profile = FunctionProfile::default();
+/// profile.stats.push(FunctionStats::from_samples(&[16.0, 17.0]));
+/// profile.timings.push([0.0, profile.stats[0].mean]);
+/// lane.path_profiles.push(profile);
+/// lane.latest_tree.set_root(frame, "FRAME");
+///
+/// // a thread that only times general functions
+/// let mut worker = ThreadProfile::new(None, "worker".to_string());
+/// let mut decode = FunctionProfile::default();
+/// decode.stats.push(FunctionStats::from_samples(&[2.0, 4.0]));
+/// decode.timings.push([0.0, decode.stats[0].mean]);
+/// worker.all_profiles.insert("DECODE", decode);
+///
+/// let mut app = PerformanceProfiler::new(Settings { update_interval_sec: 0.0, ..Settings::default() });
+/// app.threads.push(lane);
+/// app.threads.push(worker);
+///
+/// let mut server = ProfilerServer::bind(app.into_static(), "127.0.0.1:0").unwrap();
+/// let viewer = connect_remote(server.local_addr().unwrap()).unwrap();
+/// change_profiler_settings(viewer, |s| s.update_interval_sec = 0.0);
+///
+/// // the viewer is accepted by the next poll
+/// while server.viewers() == 0 {
+///    server.poll();
+///    sleep(Duration::from_millis(1));
+/// }
+/// server.send_now();
+///
+/// while open_profiler(viewer, |p| p.threads.is_empty()) {
+///    sleep(Duration::from_millis(1));
+/// }
+///
+/// // the viewers ui opens the profiler every frame it draws
+/// for _ in 0..20 {
+///    open_profiler(viewer, |_| ());
+///    sleep(Duration::from_millis(1));
+/// }
+/// open_profiler(viewer, |p| {
+///    assert!(p.warnings.is_empty());
+///    assert_eq!(p.threads.len(), 2);
+///
+///    let lane = &p.threads[0];
+///    assert_eq!(lane.name, "main");
+///    assert_eq!(lane.path_profiles[frame.index()].stats, [FunctionStats::from_samples(&[16.0, 17.0])]);
+///    assert_eq!(lane.pull_path_data(frame, 5), 16.5);
+///
+///    let worker = &p.threads[1];
+///    assert_eq!(worker.name, "worker");
+///    assert_eq!(worker.all_profiles["DECODE"].stats, [FunctionStats::from_samples(&[2.0, 4.0])]);
+///    assert_eq!(worker.pull_data("DECODE", 5), 3.0);
+/// });
+/// ```
+pub fn connect_remote(addr: impl ToSocketAddrs) -> io::Result<&'static RwLock<PerformanceProfiler>> {
+   let stream = TcpStream::connect(addr)?;
+   Ok(follow_remote(stream))
+}
+
+/// ``connect_remote`` over a unix socket
+#[cfg(unix)]
+pub fn connect_remote_unix(path: impl AsRef<Path>) -> io::Result<&'static RwLock<PerformanceProfiler>> {
+   let stream = UnixStream::connect(path)?;
+   Ok(follow_remote(stream))
+}
+
+/// a profiler kept up to date from any stream in the wire format, read on a thread of its own,
+/// malformed sessions and the end of the stream are added to ``PerformanceProfiler::warnings``
+/// ```
+/// use std::io::Cursor;
+/// use std::thread::sleep;
+/// use std::time::Duration;
+/// use triglyceride::{follow_remote, open_profiler};
+///
+/// let prof = follow_remote(Cursor::new("not a session\nend\n"));
+///
+/// while open_profiler(prof, |p| p.warnings.len()) < 2 {
+///    sleep(Duration::from_millis(1));
+/// }
+/// open_profiler(prof, |p| {
+///    assert!(p.warnings[0].starts_with("skipped a malformed session"));
+///    assert!(p.warnings[1].starts_with("the app closed the connection"));
+/// });
+/// ```
+pub fn follow_remote<R: Read + Send + 'static>(stream: R) -> &'static RwLock<PerformanceProfiler> {
+   let prof = PerformanceProfiler::new(Settings::default()).into_static();
+
+   thread::Builder::new()
+       .name("ProfilerRemoteThread".to_string())
+       .spawn(move || {
+          let warning = match read_sessions(BufReader::new(stream), prof) {
+             Ok(()) => "the app closed the connection, showing its last session".to_string(),
+             Err(error) => format!("lost the connection to the app, showing its last session: {error}"),
+          };
+          open_profiler(prof, |mut p| p.warnings.push(warning));
+       })
+       .expect("failed to spawn remote thread");
+
+   prof
+}
+
+/// applies every session of the stream until it ends or breaks, a malformed session is skipped with a warning
+fn read_sessions<R: BufRead>(mut reader: R, prof: &'static RwLock<PerformanceProfiler>) -> io::Result<()> {
+   let mut session = String::new();
+   let mut line = String::new();
+
+   loop {
+      line.clear();
+      if reader.read_line(&mut line)? == 0 { return Ok(()); }
+
+      if line.trim_end_matches(['\n', '\r']) != END {
+         session.push_str(&line);
+         continue;
+      }
+
+      match PerformanceProfiler::read_session(session.as_bytes()) {
+         Ok(snapshot) => open_profiler(prof, |mut p| p.apply_snapshot(snapshot)),
+         Err(error) => open_profiler(prof, |mut p| p.warnings.push(format!("skipped a malformed session: {error}"))),
+      }
+      session.clear();
+   }
+}
+
+impl PerformanceProfiler {
+   /// internal function, takes the threads of a newer session, lanes keep their ids by name so the
+   /// ui keeps its selection, nothing is taken while paused
+   fn apply_snapshot(&mut self, mut snapshot: PerformanceProfiler) {
+      if self.settings.paused { return; }
+
+      for lane in snapshot.threads.iter_mut() {
+         if let Some(old) = self.threads.iter().find(|old| old.name == lane.name) {
+            lane.id = old.id;
+         }
+      }
+
+      self.threads = snapshot.threads;
+   }
+}
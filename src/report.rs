@@ -0,0 +1,184 @@
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::RwLock;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::{open_profiler, PathId, PerformanceProfiler, Statistic, ThreadProfile};
+
+
+/// plain text output, for servers and cli tools that never open a window
+impl PerformanceProfiler {
+   /// every threads latest tree as indented text, one line per call path with its mean, p95,
+   /// calls per frame and share of its parent, threads without a tree list their functions instead
+   /// ```
+   /// use triglyceride::{FunctionProfile, FunctionStats, PerformanceProfiler, Settings, ThreadProfile};
+   ///
+   /// fn profile(samples: &[f64]) -> FunctionProfile {
+   ///    let mut profile = FunctionProfile::default();
+   ///    profile.stats.push(FunctionStats::from_samples(samples));
+   ///    profile.timings.push([0.0, profile.stats[0].mean]);
+   ///    profile
+   /// }
+   ///
+   /// let mut main = ThreadProfile::new(None, "main".to_string());
+   /// let frame = main.call_paths.intern(None, "FRAME");
+   /// let work = main.call_paths.intern(Some(frame), "WORK");
+   /// main.path_profiles = vec![profile(&[4.0]), profile(&[1.0, 3.0])];
+   /// main.latest_tree.set_root(frame, "FRAME");
+   /// main.latest_tree.add_child(frame, work, "WORK");
+   ///
+   /// let mut worker = ThreadProfile::new(None, "worker".to_string());
+   /// worker.all_profiles.insert("DECODE", profile(&[0.5]));
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings::default());
+   /// profiler.threads = vec![main, worker];
+   ///
+   /// assert_eq!(profiler.text_report(), "\
+   /// == main ==
+   /// FRAME 4.000ms p95 4.000ms x1 100.0%
+   ///   WORK 2.000ms p95 3.000ms x1 50.0%
+   /// == worker ==
+   ///   DECODE 0.500ms p95 0.500ms
+   /// ");
+   /// ```
+   pub fn text_report(&self) -> String {
+      let mut report = String::new();
+
+      for lane in self.threads.iter() {
+         let _ = writeln!(report, "== {} ==", lane.name);
+
+         match lane.latest_tree.root {
+            Some(root) => self.report_path(&mut report, lane, root, 0, None),
+            None => self.report_functions(&mut report, lane),
+         }
+      }
+
+      report
+   }
+
+   /// writes ``text_report`` to any writer
+   pub fn write_text_report<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+      writer.write_all(self.text_report().as_bytes())?;
+      writer.flush()
+   }
+
+   fn report_path(&self, report: &mut String, lane: &ThreadProfile, path: PathId, depth: usize, parent_ms: Option<f64>) {
+      let smoothing = self.settings.smoothing_amount;
+      let mean = lane.pull_path_statistic(path, Statistic::Mean, smoothing);
+      let p95 = lane.pull_path_statistic(path, Statistic::P95, smoothing);
+      let calls = lane.path_profiles.get(path.index())
+          .and_then(|profile| profile.latest_stats())
+          .map(|stats| stats.calls_per_frame)
+          .unwrap_or(0.0);
+
+      let share = match parent_ms {
+         Some(parent_ms) if parent_ms > 0.0 => mean / parent_ms * 100.0,
+         _ => 100.0,
+      };
+
+      let _ = writeln!(
+         report,
+         "{:indent$}{} {mean:.3}ms p95 {p95:.3}ms x{calls:.0} {share:.1}%",
+         "", lane.call_paths.name(path), indent = depth * INDENT,
+      );
+
+      let Some(node) = lane.latest_tree.nodes.get(&path) else { return; };
+      for child in node.children.iter() {
+         self.report_path(report, lane, *child, depth + 1, Some(mean));
+      }
+   }
+
+   fn report_functions(&self, report: &mut String, lane: &ThreadProfile) {
+      let smoothing = self.settings.smoothing_amount;
+
+      let mut names: Vec<_> = lane.all_profiles.keys().collect();
+      names.sort();
+      for name in names {
+         let mean = lane.pull_statistic(name, Statistic::Mean, smoothing);
+         let p95 = lane.pull_statistic(name, Statistic::P95, smoothing);
+         let _ = writeln!(report, "{:INDENT$}{name} {mean:.3}ms p95 {p95:.3}ms", "");
+      }
+   }
+}
+
+const INDENT: usize = 2;
+
+
+/// hands a ``text_report`` to a sink every ``Settings::update_interval_sec``, needs no window or egui
+/// ```no_run
+/// use triglyceride::{init_profiler, HeadlessReporter, Settings};
+///
+/// init_profiler!(PROF, Settings::default());
+///
+/// fn main() {
+///    // on its own thread
+///    HeadlessReporter::stdout(&PROF).spawn();
+///
+///    // or from the apps own loop
+///    let mut reporter = HeadlessReporter::new(&PROF, |report| eprint!("{report}"));
+///    loop {
+///       reporter.poll();
+///    }
+/// }
+/// ```
+pub struct HeadlessReporter {
+   prof: &'static RwLock<PerformanceProfiler>,
+   sink: Box<dyn FnMut(&str) + Send>,
+   last_report: Instant,
+}
+impl HeadlessReporter {
+   /// reports to a callback
+   pub fn new(prof: &'static RwLock<PerformanceProfiler>, sink: impl FnMut(&str) + Send + 'static) -> Self {
+      Self {
+         prof,
+         sink: Box::new(sink),
+         last_report: Instant::now(),
+      }
+   }
+
+   pub fn stdout(prof: &'static RwLock<PerformanceProfiler>) -> Self {
+      Self::new(prof, |report| print!("{report}"))
+   }
+
+   /// reports to a log file, socket or anything else that can be written to, write errors are dropped
+   pub fn to_writer<W: Write + Send + 'static>(prof: &'static RwLock<PerformanceProfiler>, mut writer: W) -> Self {
+      Self::new(prof, move |report| {
+         let _ = writer.write_all(report.as_bytes()).and_then(|_| writer.flush());
+      })
+   }
+
+   /// reports if ``Settings::update_interval_sec`` passed since the last report
+   pub fn poll(&mut self) {
+      let interval = open_profiler(self.prof, |p| p.settings.update_interval_sec);
+      if self.last_report.elapsed().as_secs_f64() < interval { return; }
+
+      self.report_now();
+   }
+
+   /// reports straight away
+   pub fn report_now(&mut self) {
+      self.last_report = Instant::now();
+
+      let report = open_profiler(self.prof, |p| p.text_report());
+      (self.sink)(&report);
+   }
+
+   /// reports from a thread of its own for as long as the program runs
+   pub fn spawn(mut self) -> JoinHandle<()> {
+      thread::Builder::new()
+          .name("ProfilerReportThread".to_string())
+          .spawn(move || loop {
+             let interval = open_profiler(self.prof, |p| p.settings.update_interval_sec);
+             thread::sleep(Duration::from_secs_f64(interval.max(MIN_SLEEP_SEC)));
+
+             self.report_now();
+          })
+          .expect("failed to spawn report thread")
+   }
+}
+
+/// keeps a 0 second interval from spinning
+const MIN_SLEEP_SEC: f64 = 0.01;
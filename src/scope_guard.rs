@@ -0,0 +1,172 @@
+use std::sync::RwLock;
+
+#[cfg(not(feature = "disabled"))]
+use crate::event_buffer::{record, EventKind};
+use crate::{PerformanceProfiler, StatString};
+
+
+/// ends its event when dropped, so early returns, ``?`` and panics can't leave a timer open,
+/// created with ``scope`` / ``lone_scope`` or the ``scope!`` macro,
+/// starts and ends are buffered per thread and reach the profiler when the outermost open event ends
+#[cfg(not(feature = "disabled"))]
+#[must_use = "the event ends as soon as the guard is dropped"]
+pub struct ScopeGuard {
+   profiler: &'static RwLock<PerformanceProfiler>,
+   name: StatString,
+
+   /// ``true`` for tree events, ``false`` for lone functions
+   in_tree: bool,
+}
+
+/// the ``disabled`` feature compiles every guard down to nothing
+#[cfg(feature = "disabled")]
+#[must_use = "the event ends as soon as the guard is dropped"]
+pub struct ScopeGuard;
+
+#[cfg(not(feature = "disabled"))]
+impl Drop for ScopeGuard {
+   fn drop(&mut self) {
+      // errors are kept as warnings by the profiler when the buffer is replayed
+      let kind = match self.in_tree {
+         true => EventKind::End,
+         false => EventKind::LoneEnd,
+      };
+      record(self.profiler, self.name, kind);
+   }
+}
+
+
+/// times an event and adds it to the function tree until the returned guard is dropped
+/// ```
+/// use triglyceride::{init_profiler, scope, Settings};
+///
+/// init_profiler!(PROF, Settings::default());
+///
+/// fn load(path: &str) -> Option<usize> {
+///    let _guard = scope(&PROF, "LOAD");
+///
+///    // the event still ends here
+///    if path.is_empty() { return None; }
+///
+///    Some(path.len())
+/// }
+///
+/// fn main() {
+///    load("");
+///    load("file.txt");
+/// }
+/// ```
+/// direct calls on the profiler are applied after the scopes the thread opened before them
+/// ```
+/// use triglyceride::{init_profiler, open_profiler, scope, Settings};
+///
+/// init_profiler!(PROF, Settings::default());
+///
+/// fn main() {
+///    for _ in 0..3 {
+///       let _main = scope(&PROF, "MAIN");
+///       open_profiler(&PROF, |mut p| p.time_event_start("DIRECT")).unwrap();
+///       open_profiler(&PROF, |mut p| p.time_event_end("DIRECT")).unwrap();
+///    }
+///
+///    open_profiler(&PROF, |mut p| {
+///       let lane = p.current_thread();
+///       assert!(lane.warnings.is_empty());
+///
+///       let main = lane.call_paths.get(None, "MAIN");
+///       assert!(main.is_some() && lane.call_paths.get(main, "DIRECT").is_some());
+///    });
+/// }
+/// ```
+/// a thread keeps buffering while the profiler is open instead of waiting for it
+/// ```
+/// use std::sync::mpsc::channel;
+/// use std::thread::spawn;
+/// use std::time::Duration;
+/// use triglyceride::{init_profiler, open_profiler, scope, Settings};
+///
+/// init_profiler!(PROF, Settings::default());
+///
+/// fn main() {
+///    let (done, frames_done) = channel();
+///    let worker = open_profiler(&PROF, |_| {
+///       let worker = spawn(move || {
+///          for _ in 0..10 {
+///             let _frame = scope(&PROF, "FRAME");
+///          }
+///          done.send(()).unwrap();
+///       });
+///
+///       // the ui holding the profiler for a whole draw
+///       assert!(frames_done.recv_timeout(Duration::from_secs(10)).is_ok());
+///       worker
+///    });
+///    worker.join().unwrap();
+///
+///    open_profiler(&PROF, |p| {
+///       assert!(p.threads.iter().any(|lane| lane.all_profiles.contains_key("FRAME")));
+///    });
+/// }
+/// ```
+#[cfg(not(feature = "disabled"))]
+pub fn scope(profiler: &'static RwLock<PerformanceProfiler>, name: StatString) -> ScopeGuard {
+   record(profiler, name, EventKind::Start);
+
+   ScopeGuard {
+      profiler,
+      name,
+      in_tree: true,
+   }
+}
+
+/// times a general function without adding it to the function tree until the returned guard is dropped,
+/// a thread that never times a tree event is resolved whenever the profiler is opened
+/// ```
+/// use std::thread::{sleep, spawn};
+/// use std::time::Duration;
+/// use triglyceride::{init_profiler, lone_scope, open_profiler, Settings, Statistic};
+///
+/// init_profiler!(PROF, Settings { update_interval_sec: 0.01, ..Settings::default() });
+///
+/// fn main() {
+///    spawn(|| for _ in 0..10 {
+///       let _decode = lone_scope(&PROF, "DECODE");
+///       sleep(Duration::from_millis(1));
+///    }).join().unwrap();
+///
+///    // the ui opening the profiler every frame
+///    for _ in 0..8 {
+///       open_profiler(&PROF, |_| ());
+///       sleep(Duration::from_millis(5));
+///    }
+///
+///    open_profiler(&PROF, |p| {
+///       let decode = p.threads.iter().find_map(|lane| lane.all_profiles.get("DECODE")).unwrap();
+///       assert!(decode.series(Statistic::Mean).iter().any(|[_, ms]| *ms >= 10.0));
+///    });
+/// }
+/// ```
+#[cfg(not(feature = "disabled"))]
+pub fn lone_scope(profiler: &'static RwLock<PerformanceProfiler>, name: StatString) -> ScopeGuard {
+   record(profiler, name, EventKind::LoneStart);
+
+   ScopeGuard {
+      profiler,
+      name,
+      in_tree: false,
+   }
+}
+
+/// no-op, the ``disabled`` feature never touches the profiler
+#[cfg(feature = "disabled")]
+#[inline(always)]
+pub fn scope(_profiler: &'static RwLock<PerformanceProfiler>, _name: StatString) -> ScopeGuard {
+   ScopeGuard
+}
+
+/// no-op, the ``disabled`` feature never touches the profiler
+#[cfg(feature = "disabled")]
+#[inline(always)]
+pub fn lone_scope(_profiler: &'static RwLock<PerformanceProfiler>, _name: StatString) -> ScopeGuard {
+   ScopeGuard
+}
@@ -0,0 +1,454 @@
+//! recorded sessions, everything the ui shows saved to a plain text file so it can be reopened
+//! without the profiled app running
+//!
+//! the format is line based, one record per line with tab separated fields, text fields escape
+//! ``\\``, tab and newline with a backslash, numbers are written so they parse back exactly
+//!
+//! ```text
+//! triglyceride-session  <version>
+//! setting   <name> <value>
+//! thread    <name>                                 starts a lane, everything below belongs to it
+//! function  <name>                                 a profile merged by name
+//! path      <parent index or -> <name>             a call path, indexed in the order written
+//! stat      <index> <count> <calls_per_frame> <mean_per_call> <self_time> <mean> <min> <max> <p50> <p95> <p99> <std_dev>
+//!                                                  one resolved interval of the last function or path
+//! tree      <root path index>
+//! node      <path index> <child path index>...     one node of the latest tree
+//! frame     <number> <start> <end>
+//! span      <name> <start> <end> <depth>           one span of the last frame
+//! ```
+//! unknown records and settings are skipped so older readers open newer files where they can
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use lazy_static::lazy_static;
+
+use crate::{CapturedFrame, FunctionProfile, FunctionStats, PathId, PerformanceProfiler, Settings, Span, StatString, ThreadProfile};
+
+
+/// bumped whenever a record changes meaning
+pub const SESSION_VERSION: u32 = 1;
+
+const MAGIC: &str = "triglyceride-session";
+
+
+/// saving and loading whole sessions
+impl PerformanceProfiler {
+   /// writes the settings and every threads profiles, latest tree and stored frames
+   /// ```
+   /// use triglyceride::{FunctionProfile, FunctionStats, PerformanceProfiler, Settings, ThreadProfile};
+   ///
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   /// let frame = lane.call_paths.intern(None, "FRAME");
+   /// let work = lane.call_paths.intern(Some(frame), "WORK");
+   /// let idle = lane.call_paths.intern(None, "IDLE");
+   /// let idle_work = lane.call_paths.intern(Some(idle), "WORK");
+   ///
+   /// let stats = FunctionStats::from_samples(&[0.1, 0.2, 1.0 / 3.0]);
+   /// lane.path_profiles.resize_with(lane.call_paths.len(), FunctionProfile::default);
+   /// lane.path_profiles[work.index()].timings.push([0.0, stats.mean]);
+   /// lane.path_profiles[work.index()].stats.push(stats);
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings::default());
+   /// profiler.threads.push(lane);
+   ///
+   /// let mut file = vec![];
+   /// profiler.write_session(&mut file).unwrap();
+   /// let loaded = PerformanceProfiler::read_session(file.as_slice()).unwrap();
+   /// let lane = &loaded.threads[0];
+   ///
+   /// // numbers come back exactly, the same name under two parents stays two paths
+   /// assert_eq!(lane.path_profiles[work.index()].stats, [stats]);
+   /// assert!(lane.path_profiles[idle_work.index()].stats.is_empty());
+   /// assert_eq!(lane.call_paths.chain(work), ["FRAME", "WORK"]);
+   /// assert_eq!(lane.call_paths.chain(idle_work), ["IDLE", "WORK"]);
+   /// ```
+   pub fn write_session<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+      writeln!(writer, "{MAGIC}\t{SESSION_VERSION}")?;
+
+      let settings = &self.settings;
+      writeln!(writer, "setting\tstored_data_amount\t{}", settings.stored_data_amount)?;
+      writeln!(writer, "setting\tstored_cash_amount\t{}", settings.stored_cash_amount)?;
+      writeln!(writer, "setting\tupdate_interval_sec\t{}", settings.update_interval_sec)?;
+      writeln!(writer, "setting\tsmoothing_amount\t{}", settings.smoothing_amount)?;
+      writeln!(writer, "setting\tstored_frame_amount\t{}", settings.stored_frame_amount)?;
+      writeln!(writer, "setting\tspike_threshold_ms\t{}", settings.spike_threshold_ms)?;
+
+      for lane in self.threads.iter() {
+         writeln!(writer, "thread\t{}", escape(&lane.name))?;
+
+         let mut names: Vec<&StatString> = lane.all_profiles.keys().collect();
+         names.sort();
+         for name in names {
+            writeln!(writer, "function\t{}", escape(name))?;
+            write_stats(&mut writer, &lane.all_profiles[name])?;
+         }
+
+         for (index, profile) in lane.path_profiles.iter().enumerate() {
+            let path = PathId(index);
+            let parent = match lane.call_paths.parent(path) {
+               None => "-".to_string(),
+               Some(parent) => parent.index().to_string(),
+            };
+
+            writeln!(writer, "path\t{parent}\t{}", escape(lane.call_paths.name(path)))?;
+            write_stats(&mut writer, profile)?;
+         }
+
+         if let Some(root) = lane.latest_tree.root {
+            writeln!(writer, "tree\t{}", root.index())?;
+
+            let mut nodes: Vec<_> = lane.latest_tree.nodes.values().collect();
+            nodes.sort_by_key(|node| node.path);
+            for node in nodes {
+               write!(writer, "node\t{}", node.path.index())?;
+               for child in node.children.iter() {
+                  write!(writer, "\t{}", child.index())?;
+               }
+               writeln!(writer)?;
+            }
+         }
+
+         for frame in lane.frames.iter() {
+            writeln!(writer, "frame\t{}\t{}\t{}", frame.number, frame.start, frame.end)?;
+            for span in frame.spans.iter() {
+               writeln!(writer, "span\t{}\t{}\t{}\t{}", escape(span.name), span.start, span.end, span.depth)?;
+            }
+         }
+      }
+
+      writer.flush()
+   }
+
+   /// writes ``write_session`` to a file, replacing it if it exists
+   pub fn save_session(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+      self.write_session(BufWriter::new(File::create(path)?))
+   }
+
+   /// a profiler holding a saved session, meant for viewing, the lanes have no ``ThreadProfile::thread_id``
+   /// so they never collide with a running thread, malformed files are ``ErrorKind::InvalidData``,
+   /// the loaded numbers stay as they were however often the profiler is opened
+   /// ```
+   /// use triglyceride::{open_profiler, FunctionProfile, FunctionStats, PerformanceProfiler, Settings, ThreadProfile};
+   ///
+   /// let mut frame = FunctionProfile::default();
+   /// for samples in [[4.0, 5.0], [6.0, 4.0]] {
+   ///    let stats = FunctionStats::from_samples(&samples);
+   ///    frame.timings.push([frame.stats.len() as f64, stats.mean]);
+   ///    frame.stats.push(stats);
+   /// }
+   ///
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   /// lane.all_profiles.insert("FRAME", frame);
+   ///
+   /// // resolving as often as it can
+   /// let mut profiler = PerformanceProfiler::new(Settings { update_interval_sec: 0.0, ..Settings::default() });
+   /// profiler.threads.push(lane);
+   ///
+   /// let mut file = vec![];
+   /// profiler.write_session(&mut file).unwrap();
+   /// let loaded = PerformanceProfiler::read_session(file.as_slice()).unwrap().into_static();
+   ///
+   /// let stats = |p: &PerformanceProfiler| p.threads[0].all_profiles["FRAME"].stats.clone();
+   /// let before = open_profiler(loaded, |p| stats(&p));
+   /// for _ in 0..20 {
+   ///    open_profiler(loaded, |_| ());
+   /// }
+   /// assert_eq!(before.len(), 2);
+   /// assert_eq!(open_profiler(loaded, |p| stats(&p)), before);
+   /// ```
+   pub fn read_session<R: BufRead>(reader: R) -> std::io::Result<Self> {
+      let mut lines = reader.lines().enumerate();
+
+      let Some((_, header)) = lines.next() else { return Err(invalid(0, "empty file")); };
+      let header = header?;
+      let version = match header.split('\t').collect::<Vec<_>>().as_slice() {
+         [MAGIC, version] => version.parse::<u32>().map_err(|_| invalid(1, "bad version"))?,
+         _ => return Err(invalid(1, "not a triglyceride session")),
+      };
+      if version > SESSION_VERSION {
+         return Err(invalid(1, &format!("session version {version} is newer than {SESSION_VERSION}")));
+      }
+
+      let mut profiler = PerformanceProfiler::new(Settings::default());
+      let mut names = Names::default();
+
+      // what ``stat`` and ``span`` records attach to
+      let mut target: Option<Target> = None;
+
+      for (number, line) in lines {
+         let line = line?;
+         let number = number + 1;
+         if line.is_empty() { continue; }
+
+         let fields: Vec<String> = line.split('\t').map(unescape).collect();
+         let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+
+         if fields[0] == "setting" {
+            let [_, name, value] = fields[..] else { return Err(invalid(number, "bad setting")); };
+            read_setting(&mut profiler.settings, name, value).ok_or_else(|| invalid(number, "bad setting"))?;
+            continue;
+         }
+
+         if fields[0] == "thread" {
+            let [_, name] = fields[..] else { return Err(invalid(number, "bad thread")); };
+            let mut lane = ThreadProfile::new(None, name.to_string());
+            lane.is_actually_active_or_not = false;
+            profiler.threads.push(lane);
+            target = None;
+            continue;
+         }
+
+         let Some(lane) = profiler.threads.last_mut() else {
+            return Err(invalid(number, "record before the first thread"));
+         };
+
+         match fields[0] {
+            "function" => {
+               let [_, name] = fields[..] else { return Err(invalid(number, "bad function")); };
+               let name = names.get(name);
+               lane.all_profiles.insert(name, FunctionProfile::default());
+               target = Some(Target::Function(name));
+            }
+
+            "path" => {
+               let [_, parent, name] = fields[..] else { return Err(invalid(number, "bad path")); };
+               let parent = match parent {
+                  "-" => None,
+                  parent => Some(read_path(lane, parent).ok_or_else(|| invalid(number, "bad path parent"))?),
+               };
+
+               let path = lane.call_paths.intern(parent, names.get(name));
+               if path.index() != lane.path_profiles.len() {
+                  return Err(invalid(number, "repeated path"));
+               }
+               lane.path_profiles.push(FunctionProfile::default());
+               target = Some(Target::Path(path));
+            }
+
+            "stat" => {
+               let profile = match target {
+                  Some(Target::Function(name)) => lane.all_profiles.get_mut(name),
+                  Some(Target::Path(path)) => lane.path_profiles.get_mut(path.index()),
+                  _ => None,
+               };
+               let profile = profile.ok_or_else(|| invalid(number, "stat outside a function or path"))?;
+               let (index, stats) = read_stats(&fields[1..]).ok_or_else(|| invalid(number, "bad stat"))?;
+
+               profile.timings.push([index, stats.mean]);
+               profile.stats.push(stats);
+            }
+
+            "tree" => {
+               let [_, root] = fields[..] else { return Err(invalid(number, "bad tree")); };
+               let root = read_path(lane, root).ok_or_else(|| invalid(number, "bad tree root"))?;
+               lane.latest_tree.set_root(root, lane.call_paths.name(root));
+            }
+
+            "node" => {
+               let parent = fields.get(1)
+                   .and_then(|parent| read_path(lane, parent))
+                   .ok_or_else(|| invalid(number, "bad node"))?;
+
+               for child in fields[2..].iter() {
+                  let child = read_path(lane, child).ok_or_else(|| invalid(number, "bad node child"))?;
+                  lane.latest_tree.add_child(parent, child, lane.call_paths.name(child));
+               }
+            }
+
+            "frame" => {
+               let [_, frame_number, start, end] = fields[..] else { return Err(invalid(number, "bad frame")); };
+               let frame = (|| Some(CapturedFrame {
+                  number: frame_number.parse().ok()?,
+                  start: start.parse().ok()?,
+                  end: end.parse().ok()?,
+                  spans: vec![],
+               }))().ok_or_else(|| invalid(number, "bad frame"))?;
+
+               lane.frames_stored = frame.number + 1;
+               lane.frames.push_back(frame);
+               target = Some(Target::Frame);
+            }
+
+            "span" => {
+               let [_, name, start, end, depth] = fields[..] else { return Err(invalid(number, "bad span")); };
+               let (Some(Target::Frame), Some(frame)) = (target, lane.frames.back_mut()) else {
+                  return Err(invalid(number, "span outside a frame"));
+               };
+
+               let span = (|| Some(Span {
+                  name: names.get(name),
+                  start: start.parse().ok()?,
+                  end: end.parse().ok()?,
+                  depth: depth.parse().ok()?,
+               }))().ok_or_else(|| invalid(number, "bad span"))?;
+
+               frame.spans.push(span);
+            }
+
+            // written by a newer version
+            _ => {}
+         }
+      }
+
+      Ok(profiler)
+   }
+
+   /// reads a file written by ``save_session``, the viewer and ``spawn_disconnected_session_window``
+   /// open it like this and keep showing it unchanged
+   /// ```
+   /// use triglyceride::{open_profiler, FunctionProfile, FunctionStats, PerformanceProfiler, Settings, ThreadProfile};
+   ///
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   /// let frame = lane.call_paths.intern(None, "FRAME");
+   /// let mut profile = FunctionProfile::default();
+   /// profile.stats.push(FunctionStats::from_samples(&[16.0, 17.0]));
+   /// profile.timings.push([0.0, profile.stats[0].mean]);
+   /// lane.path_profiles.push(profile);
+   /// lane.latest_tree.set_root(frame, "FRAME");
+   ///
+   /// let mut profiler = PerformanceProfiler::new(Settings { update_interval_sec: 0.0, ..Settings::default() });
+   /// profiler.threads.push(lane);
+   ///
+   /// let path = std::env::temp_dir().join(format!("triglyceride-doctest-{}.trisession", std::process::id()));
+   /// profiler.save_session(&path).unwrap();
+   /// let viewed = PerformanceProfiler::load_session(&path).unwrap().into_static();
+   /// std::fs::remove_file(&path).unwrap();
+   ///
+   /// // the window opens the profiler every frame it draws
+   /// for _ in 0..20 {
+   ///    open_profiler(viewed, |_| ());
+   /// }
+   /// open_profiler(viewed, |p| assert_eq!(p.threads[0].pull_path_data(frame, 5), 16.5));
+   /// ```
+   pub fn load_session(path: impl AsRef<Path>) -> std::io::Result<Self> {
+      Self::read_session(BufReader::new(File::open(path)?))
+   }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Target {
+   Function(StatString),
+   Path(PathId),
+   Frame,
+}
+
+/// loaded names live as long as the program, each distinct name is only leaked once,
+/// shared by every read so a viewer following a stream of sessions doesn't leak a copy per session
+struct Names(MutexGuard<'static, HashMap<String, StatString>>);
+impl Default for Names {
+   fn default() -> Self {
+      lazy_static! {
+         static ref LOADED_NAMES: Mutex<HashMap<String, StatString>> = Mutex::default();
+      }
+
+      Self(LOADED_NAMES.lock().unwrap_or_else(PoisonError::into_inner))
+   }
+}
+impl Names {
+   fn get(&mut self, name: &str) -> StatString {
+      if let Some(name) = self.0.get(name) {
+         return name;
+      }
+
+      let leaked: StatString = Box::leak(name.to_string().into_boxed_str());
+      self.0.insert(name.to_string(), leaked);
+      leaked
+   }
+}
+
+fn write_stats<W: Write>(writer: &mut W, profile: &FunctionProfile) -> std::io::Result<()> {
+   for (timing, stats) in profile.timings.iter().zip(profile.stats.iter()) {
+      writeln!(
+         writer,
+         "stat\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+         timing[0], stats.count, stats.calls_per_frame, stats.mean_per_call, stats.self_time,
+         stats.mean, stats.min, stats.max, stats.p50, stats.p95, stats.p99, stats.std_dev,
+      )?;
+   }
+
+   Ok(())
+}
+
+fn read_stats(fields: &[&str]) -> Option<(f64, FunctionStats)> {
+   let [index, count, calls_per_frame, mean_per_call, self_time, mean, min, max, p50, p95, p99, std_dev] = fields[..] else {
+      return None;
+   };
+
+   Some((index.parse().ok()?, FunctionStats {
+      count: count.parse().ok()?,
+      calls_per_frame: calls_per_frame.parse().ok()?,
+      mean_per_call: mean_per_call.parse().ok()?,
+      self_time: self_time.parse().ok()?,
+      mean: mean.parse().ok()?,
+      min: min.parse().ok()?,
+      max: max.parse().ok()?,
+      p50: p50.parse().ok()?,
+      p95: p95.parse().ok()?,
+      p99: p99.parse().ok()?,
+      std_dev: std_dev.parse().ok()?,
+   }))
+}
+
+/// ``None`` for bad values, unknown settings are skipped
+fn read_setting(settings: &mut Settings, name: &str, value: &str) -> Option<()> {
+   match name {
+      "stored_data_amount" => settings.stored_data_amount = value.parse().ok()?,
+      "stored_cash_amount" => settings.stored_cash_amount = value.parse().ok()?,
+      "update_interval_sec" => settings.update_interval_sec = value.parse().ok()?,
+      "smoothing_amount" => settings.smoothing_amount = value.parse().ok()?,
+      "stored_frame_amount" => settings.stored_frame_amount = value.parse().ok()?,
+      "spike_threshold_ms" => settings.spike_threshold_ms = value.parse().ok()?,
+      _ => {}
+   }
+
+   Some(())
+}
+
+/// a path index that was already written
+fn read_path(lane: &ThreadProfile, index: &str) -> Option<PathId> {
+   let index: usize = index.parse().ok()?;
+   (index < lane.call_paths.len()).then_some(PathId(index))
+}
+
+fn invalid(line: usize, message: &str) -> Error {
+   Error::new(ErrorKind::InvalidData, format!("line {line}: {message}"))
+}
+
+fn escape(text: &str) -> String {
+   let mut out = String::with_capacity(text.len());
+   for c in text.chars() {
+      match c {
+         '\\' => out.push_str("\\\\"),
+         '\t' => out.push_str("\\t"),
+         '\n' => out.push_str("\\n"),
+         '\r' => out.push_str("\\r"),
+         c => out.push(c),
+      }
+   }
+   out
+}
+
+fn unescape(text: &str) -> String {
+   let mut out = String::with_capacity(text.len());
+   let mut chars = text.chars();
+   while let Some(c) = chars.next() {
+      if c != '\\' {
+         out.push(c);
+         continue;
+      }
+
+      match chars.next() {
+         Some('t') => out.push('\t'),
+         Some('n') => out.push('\n'),
+         Some('r') => out.push('\r'),
+         Some(c) => out.push(c),
+         None => out.push('\\'),
+      }
+   }
+   out
+}
@@ -0,0 +1,665 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::{self, Thread, ThreadId};
+
+use crate::captured_frame::{CapturedFrame, Span};
+use crate::function_profile::{FunctionProfile, get_ct, Statistic};
+use crate::profiler::Settings;
+use crate::profiler_error::ProfilerError;
+use crate::StatString;
+use crate::utils::tree::{CallPaths, PathId, Tree};
+
+
+/// all the data collected from a single thread, every thread that times an event gets its own
+/// lane with its own function tree, so worker threads never touch the main loops tree
+#[derive(Debug)]
+pub struct ThreadProfile {
+   /// id of this lane, what the ui keys its selections on
+   pub id: LaneId,
+
+   /// id of the thread this lane records, ``None`` for lanes loaded from a session
+   pub thread_id: Option<ThreadId>,
+
+   /// thread name, falls back to the formatted id for unnamed threads
+   pub name: String,
+
+   /// all timed functions on this thread merged by name ``HashMap<function_name, profile>``
+   pub all_profiles: HashMap<StatString, FunctionProfile>,
+
+   /// every call path of an event on this thread
+   pub call_paths: CallPaths,
+
+   /// timings of each call path, indexed by ``PathId::index``
+   pub path_profiles: Vec<FunctionProfile>,
+
+   /// latest computed function tree
+   pub latest_tree: Tree,
+
+   /// queues a tree processes for the next iteration
+   pub queue_processes_tree: bool,
+
+   /// instrumentation mistakes on this thread, deduplicated and shown as a warning banner in the ui
+   pub warnings: Vec<ProfilerError>,
+
+   /// the last ``Settings::stored_frame_amount`` complete frames, oldest first
+   pub frames: VecDeque<CapturedFrame>,
+
+   pub(crate) is_actually_active_or_not: bool,
+   /// number of a stored frame that went over ``Settings::spike_threshold_ms``, taken by the profiler to pause
+   pub(crate) spike: Option<u64>,
+   /// ms of the last resolve, on the same clock as the events so a replayed burst resolves as it happened,
+   /// ``None`` until the first one, a lane can be created long after its first events
+   pub(crate) last_dump: Option<f64>,
+   pub(crate) processioning_tree: bool,
+   pub(crate) active_tree: Tree,
+   pub(crate) traverser: Vec<PathId>,
+   pub(crate) active_frame: CapturedFrame,
+
+   /// events that haven't ended yet, innermost last
+   open_spans: Vec<OpenSpan>,
+   ticks_since_start: u32,
+   ticks_since_last_dump: u32,
+   pub(crate) frames_stored: u64,
+
+   /// ms spent in ``resolve_profiler`` since the profiler last collected it
+   pub(crate) resolve_ms: f64,
+
+   inner_constant_reference: Option<StatString>,
+
+   /// first start event
+   outermost_upper: Option<StatString>,
+
+   /// last end event
+   outermost_lower: Option<StatString>,
+}
+impl ThreadProfile {
+   /// creates a lane for the thread it's called from
+   pub fn for_current_thread() -> Self {
+      Self::for_thread(&thread::current())
+   }
+
+   /// named after the thread, or its id if it has no name
+   pub fn for_thread(thread: &Thread) -> Self {
+      let name = match thread.name() {
+         None => format!("{:?}", thread.id()),
+         Some(name) => name.to_string(),
+      };
+
+      Self::new(Some(thread.id()), name)
+   }
+
+   pub fn new(thread_id: Option<ThreadId>, name: String) -> Self {
+      Self {
+         id: LaneId::next(),
+         thread_id,
+         name,
+         all_profiles: Default::default(),
+         call_paths: Default::default(),
+         path_profiles: vec![],
+
+         is_actually_active_or_not: true,
+         spike: None,
+         latest_tree: Default::default(),
+         last_dump: None,
+         queue_processes_tree: false,
+         warnings: vec![],
+         frames: VecDeque::new(),
+         processioning_tree: false,
+         active_tree: Default::default(),
+         traverser: vec![],
+         active_frame: CapturedFrame::default(),
+         open_spans: vec![],
+
+         ticks_since_start: 0,
+         ticks_since_last_dump: 0,
+         frames_stored: 0,
+         resolve_ms: 0.0,
+         inner_constant_reference: None,
+         outermost_upper: None,
+         outermost_lower: None,
+      }
+   }
+
+   /// starts a profiler for a general function, use event loop variant for a function tree
+   pub fn start_time_function(&mut self, name: StatString) {
+      self.start_time_function_at(name, get_ct());
+   }
+
+   /// ``start_time_function`` with a timestamp taken earlier, used when replaying buffered events
+   pub(crate) fn start_time_function_at(&mut self, name: StatString, now: f64) {
+      if !self.is_actually_active_or_not { return; }
+
+      self.all_profiles.entry(name).or_default().start(now);
+   }
+
+
+   /// ends a profiler for a general function, use event loop variant for a function tree
+   pub fn end_time_function(&mut self, name: StatString) -> Result<(), ProfilerError> {
+      self.end_time_function_at(name, get_ct())
+   }
+
+   /// ``end_time_function`` with a timestamp taken earlier
+   pub(crate) fn end_time_function_at(&mut self, name: StatString, now: f64) -> Result<(), ProfilerError> {
+      if !self.is_actually_active_or_not { return Ok(()); }
+
+      match self.all_profiles.get_mut(name) {
+         None => {
+            Err(self.report(ProfilerError::UnbalancedEnd { name, expected: None }))
+         }
+         Some(profile) => {
+            profile.end(now);
+            Ok(())
+         }
+      }
+   }
+
+   /// adds one call of a general function that was timed somewhere else, e.g. across threads
+   pub fn add_function_call(&mut self, name: StatString, elapsed_ms: f64) {
+      if !self.is_actually_active_or_not { return; }
+
+      self.all_profiles.entry(name).or_default().add_call(elapsed_ms);
+   }
+
+   /// internal function, ``false`` until the first tree event, such lanes never end a frame to resolve on
+   pub(crate) fn has_tree(&self) -> bool {
+      self.outermost_upper.is_some()
+   }
+
+   /// internal function, remembers the error for the ui and passes it on
+   fn report(&mut self, error: ProfilerError) -> ProfilerError {
+      if !self.warnings.contains(&error) {
+         if self.warnings.len() >= MAX_WARNINGS {
+            self.warnings.remove(0);
+         }
+         self.warnings.push(error.clone());
+      }
+
+      error
+   }
+
+   /// internal function, interns a path and makes sure it has a profile
+   fn intern_path(&mut self, parent: Option<PathId>, name: StatString) -> PathId {
+      let path = self.call_paths.intern(parent, name);
+      if self.path_profiles.len() <= path.index() {
+         self.path_profiles.resize_with(path.index() + 1, FunctionProfile::default);
+      }
+
+      path
+   }
+
+   /// internal function, path of the constant reference if one is set, it's the parent of every outermost event
+   fn reference_path(&mut self) -> Option<PathId> {
+      let reference = self.inner_constant_reference?;
+      Some(self.intern_path(None, reference))
+   }
+
+   /// internal function
+   fn at_outermost_upper(&mut self, settings: &Settings, upper: StatString, now: f64) -> Result<(), ProfilerError> {
+      self.finish_frame(settings, now);
+      self.resolve_profiler_at(settings, true, now);
+
+      // start processes if queued, the last tree stays up while profiling is off
+      if self.queue_processes_tree {
+         self.processioning_tree = true;
+         self.queue_processes_tree = false;
+      }
+      if !self.is_actually_active_or_not || settings.paused {
+         self.processioning_tree = false;
+      }
+
+      let Some(lower) = self.outermost_lower else {
+         self.abandon_tree();
+         return Err(self.report(ProfilerError::MissingRoot { name: upper }));
+      };
+
+      // invalid check
+      if self.inner_constant_reference.is_none() && upper != lower {
+         self.abandon_tree();
+         return Err(self.report(ProfilerError::MismatchedNesting { upper, lower }));
+      }
+
+      // start tree, without a reference the outermost event becomes the root as it's added
+      if self.processioning_tree {
+         self.latest_tree = std::mem::take(&mut self.active_tree);
+         self.active_tree.clear();
+         self.traverser.clear();
+
+         if let (Some(reference), Some(path)) = (self.inner_constant_reference, self.reference_path()) {
+            self.active_tree.set_root(path, reference);
+            self.traverser.push(path);
+         }
+      }
+
+      Ok(())
+   }
+
+   /// internal function, stores the active frame and starts the next one
+   fn finish_frame(&mut self, settings: &Settings, now: f64) {
+      for profile in self.all_profiles.values_mut().chain(self.path_profiles.iter_mut()) {
+         profile.end_frame();
+      }
+
+      // anything still open was never ended, cut it off at the frame boundary
+      for open in self.open_spans.drain(..) {
+         self.active_frame.spans[open.index].end = now;
+      }
+
+      let mut frame = std::mem::take(&mut self.active_frame);
+      frame.end = now;
+      self.active_frame.start = now;
+
+      // paused keeps the stored frames as they were
+      if !self.is_actually_active_or_not || settings.paused || frame.spans.is_empty() { return; }
+
+      frame.number = self.frames_stored;
+      self.frames_stored += 1;
+
+      if settings.spike_threshold_ms > 0.0 && frame.duration() > settings.spike_threshold_ms {
+         self.spike = Some(frame.number);
+      }
+
+      self.frames.push_back(frame);
+      while self.frames.len() > settings.stored_frame_amount as usize {
+         self.frames.pop_front();
+      }
+   }
+
+   /// internal function, records the start of an events span and times its call path,
+   /// ``None`` while profiling is off
+   fn open_span(&mut self, name: StatString, now: f64) -> Option<PathId> {
+      if !self.is_actually_active_or_not { return None; }
+
+      let parent = match self.open_spans.last() {
+         Some(open) => Some(open.path),
+         None => self.reference_path(),
+      };
+      let path = self.intern_path(parent, name);
+      self.path_profiles[path.index()].start(now);
+
+      self.open_spans.push(OpenSpan { index: self.active_frame.spans.len(), path, children: 0.0 });
+      self.active_frame.spans.push(Span {
+         name,
+         start: now,
+         end: now,
+         depth: self.open_spans.len() as u32 - 1,
+      });
+
+      Some(path)
+   }
+
+   /// internal function, records the end of the innermost span with a matching name,
+   /// and takes the time spent in child events off the functions self time
+   fn close_span(&mut self, name: StatString, now: f64) {
+      let reference_path = self.reference_path();
+
+      let spans = &mut self.active_frame.spans;
+      let Some(position) = self.open_spans.iter().rposition(|open| spans[open.index].name == name) else { return; };
+
+      while self.open_spans.len() > position {
+         let Some(open) = self.open_spans.pop() else { break; };
+
+         let span = &mut spans[open.index];
+         span.end = now;
+         let duration = span.duration();
+
+         let path_profile = &mut self.path_profiles[open.path.index()];
+         path_profile.end(now);
+         path_profile.remove_child_time(open.children);
+
+         if let Some(profile) = self.all_profiles.get_mut(span.name) {
+            profile.remove_child_time(open.children);
+         }
+
+         match self.open_spans.last_mut() {
+            Some(parent) => parent.children += duration,
+
+            // outermost events are children of the constant reference
+            None => {
+               if let Some(profile) = self.inner_constant_reference.and_then(|r| self.all_profiles.get_mut(r)) {
+                  profile.remove_child_time(duration);
+               }
+               if let Some(path) = reference_path {
+                  self.path_profiles[path.index()].remove_child_time(duration);
+               }
+            }
+         }
+      }
+   }
+
+   /// internal function, drops the half built tree after a nesting error, it's requeued on the next resolve
+   fn abandon_tree(&mut self) {
+      self.processioning_tree = false;
+      self.active_tree.clear();
+      self.traverser.clear();
+   }
+
+   /// internal function, adds an event to the tree under the innermost open event
+   fn add_to_tree(&mut self, name: StatString, path: PathId) -> Result<(), ProfilerError> {
+      if !self.processioning_tree { return Ok(()); }
+
+      match (self.traverser.last(), self.active_tree.root) {
+         (Some(&parent), _) => {
+            self.active_tree.add_child(parent, path, name);
+         }
+         (None, None) => {
+            self.active_tree.set_root(path, name);
+         }
+         (None, Some(_)) => {
+            return Err(self.report(ProfilerError::UnknownParent { name }));
+         }
+      }
+
+      self.traverser.push(path);
+      Ok(())
+   }
+
+   /// starts profiling an inner event function
+   pub fn time_event_start(&mut self, settings: &Settings, name: StatString) -> Result<(), ProfilerError> {
+      self.time_event_start_at(settings, name, get_ct())
+   }
+
+   /// ``time_event_start`` with a timestamp taken earlier, used when replaying buffered events
+   pub(crate) fn time_event_start_at(&mut self, settings: &Settings, name: StatString, now: f64) -> Result<(), ProfilerError> {
+      let result = match self.outermost_upper {
+         None => {
+            self.outermost_upper = Some(name);
+            self.active_frame.start = now;
+            Ok(())
+         }
+
+         Some(outer) if outer == name => self.at_outermost_upper(settings, name, now),
+
+         // not outermost loop
+         Some(_) => Ok(()),
+      };
+
+      let tree_result = match self.open_span(name, now) {
+         None => Ok(()),
+         Some(path) => self.add_to_tree(name, path),
+      };
+
+      self.start_time_function_at(name, now);
+
+      result.and(tree_result)
+   }
+
+   /// ends profiling an inner event function
+   pub fn time_event_end(&mut self, name: StatString) -> Result<(), ProfilerError> {
+      self.time_event_end_at(name, get_ct())
+   }
+
+   /// ``time_event_end`` with a timestamp taken earlier
+   pub(crate) fn time_event_end_at(&mut self, name: StatString, now: f64) -> Result<(), ProfilerError> {
+      let result = self.end_time_function_at(name, now);
+      self.close_span(name, now);
+
+      // is checked in outermost upper
+      self.outermost_lower = Some(name);
+
+      // function tree
+      if self.processioning_tree {
+         match self.traverser.last().map(|path| self.call_paths.name(*path)) {
+            Some(open) if open == name => {
+               self.traverser.pop();
+            }
+            expected => {
+               // close everything left open inside the ended event so the next frame lines up again
+               if let Some(index) = self.traverser.iter().rposition(|path| self.call_paths.name(*path) == name) {
+                  self.traverser.truncate(index);
+               }
+
+               return Err(self.report(ProfilerError::UnbalancedEnd { name, expected }));
+            }
+         }
+      }
+
+      result
+   }
+
+   /// sets a reference that is called every frame instead of an overarching function to start the tree
+   // TODO hacky
+   pub fn set_constant_reference(&mut self, name: StatString) {
+      let now = get_ct();
+      let first_call = self.inner_constant_reference.is_none();
+      self.inner_constant_reference = Some(name);
+
+      let path = match self.is_actually_active_or_not {
+         true => self.reference_path(),
+         false => None,
+      };
+
+      if !first_call {
+         // already reported
+         let _ = self.end_time_function_at(name, now);
+
+         if let Some(path) = path {
+            self.path_profiles[path.index()].end(now);
+         }
+      }
+
+      self.start_time_function_at(name, now);
+
+      if let Some(path) = path {
+         self.path_profiles[path.index()].start(now);
+      }
+   }
+
+
+   /// calculate averages, only runs every ``Settings::update_interval``,
+   /// the interval is measured between event timestamps so frames applied late still resolve as they ran
+   /// ```
+   /// use std::thread::sleep;
+   /// use std::time::Duration;
+   /// use triglyceride::{init_profiler, open_profiler, scope, Settings};
+   ///
+   /// init_profiler!(PROF, Settings { update_interval_sec: 0.01, ..Settings::default() });
+   ///
+   /// fn main() {
+   ///    // nothing opens the profiler, every frame waits in the buffer until the end
+   ///    for _ in 0..40 {
+   ///       let _frame = scope(&PROF, "FRAME");
+   ///       let _work = scope(&PROF, "WORK");
+   ///       sleep(Duration::from_millis(2));
+   ///    }
+   ///
+   ///    open_profiler(&PROF, |mut p| {
+   ///       let lane = p.current_thread();
+   ///       # if cfg!(feature = "disabled") { return; }
+   ///       assert_eq!(lane.latest_tree.root_name(), Some("FRAME"));
+   ///       assert!(lane.pull_data("WORK", 1) >= 2.0);
+   ///    });
+   /// }
+   /// ```
+   pub fn resolve_profiler(&mut self, settings: &Settings, queue_tree: bool) {
+      self.resolve_profiler_at(settings, queue_tree, get_ct());
+   }
+
+   /// ``resolve_profiler`` at the time of a replayed event
+   pub(crate) fn resolve_profiler_at(&mut self, settings: &Settings, queue_tree: bool, now: f64) {
+      let start = get_ct();
+
+      if self.is_actually_active_or_not != settings.active {
+         if !settings.active {
+            self.inner_resolve(settings, queue_tree, now);
+         }
+         self.is_actually_active_or_not = settings.active;
+      }
+
+      if self.is_actually_active_or_not { self.inner_resolve(settings, queue_tree, now); }
+
+      self.resolve_ms += get_ct() - start;
+   }
+
+   /// internal function
+   fn inner_resolve(&mut self, settings: &Settings, queue_tree: bool, now: f64) {
+      self.ticks_since_start += 1;
+      self.ticks_since_last_dump += 1;
+
+      let last_dump = *self.last_dump.get_or_insert(now);
+      if (now - last_dump > settings.update_interval_sec * 1000.0) && self.ticks_since_last_dump > 3 {
+         self.ticks_since_last_dump = 0;
+         self.last_dump = Some(now);
+
+         // timing keeps running while paused, the collected frames just never make it into the stats
+         if settings.paused {
+            for profile in self.all_profiles.values_mut().chain(self.path_profiles.iter_mut()) {
+               profile.discard_frames();
+            }
+            return;
+         }
+
+         for (name, profile) in self.all_profiles.iter_mut() {
+            // threads without a tree never finish a frame, treat the interval as one
+            profile.end_frame();
+
+            let cull_first_average = self.inner_constant_reference == Some(*name);
+            profile.resolve(settings.stored_cash_amount, settings.stored_data_amount, cull_first_average, self.ticks_since_start);
+         }
+
+         let reference_path = self.reference_path();
+         for (index, profile) in self.path_profiles.iter_mut().enumerate() {
+            profile.end_frame();
+
+            let cull_first_average = reference_path.is_some_and(|path| path.index() == index);
+            profile.resolve(settings.stored_cash_amount, settings.stored_data_amount, cull_first_average, self.ticks_since_start);
+         }
+
+         self.queue_processes_tree = queue_tree;
+      }
+   }
+
+   /// smoothed elapsed time in ms of a function on this thread, averaged over ``Settings::smoothing_amount`` datapoints
+   pub fn pull_data(&self, node: StatString, smoothing_amount: u32) -> f64 {
+      self.pull_statistic(node, Statistic::Mean, smoothing_amount)
+   }
+
+   /// smoothed self time in ms of a function on this thread, excludes time spent in child events
+   /// ```
+   /// use std::thread::sleep;
+   /// use std::time::Duration;
+   /// use triglyceride::{Settings, ThreadProfile};
+   ///
+   /// let settings = Settings { update_interval_sec: 0.0, ..Settings::default() };
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   ///
+   /// for _ in 0..6 {
+   ///    lane.time_event_start(&settings, "FRAME").unwrap();
+   ///    sleep(Duration::from_millis(2));
+   ///
+   ///    lane.time_event_start(&settings, "WORK").unwrap();
+   ///    sleep(Duration::from_millis(1));
+   ///    lane.time_event_end("WORK").unwrap();
+   ///
+   ///    lane.time_event_end("FRAME").unwrap();
+   /// }
+   ///
+   /// let frame = lane.pull_data("FRAME", 1);
+   /// let work = lane.pull_data("WORK", 1);
+   ///
+   /// // a leaf is all self time, the parent keeps what its child didn't take
+   /// assert_eq!(lane.pull_self_data("WORK", 1), work);
+   /// assert!((lane.pull_self_data("FRAME", 1) - (frame - work)).abs() < 1e-9);
+   /// assert!(lane.pull_self_data("FRAME", 1) >= 2.0);
+   /// ```
+   pub fn pull_self_data(&self, node: StatString, smoothing_amount: u32) -> f64 {
+      self.pull_statistic(node, Statistic::SelfTime, smoothing_amount)
+   }
+
+   /// any statistic of a function on this thread, averaged over the last ``smoothing_amount`` intervals
+   pub fn pull_statistic(&self, node: StatString, statistic: Statistic, smoothing_amount: u32) -> f64 {
+      match self.all_profiles.get(node) {
+         None => 0.0,
+         Some(profile) => profile.pull_smoothed(statistic, smoothing_amount),
+      }
+   }
+
+   /// smoothed elapsed time in ms of one call path, only counts calls made from that exact chain of parents
+   /// ```
+   /// use std::thread::sleep;
+   /// use std::time::Duration;
+   /// use triglyceride::{Settings, ThreadProfile};
+   ///
+   /// let settings = Settings { update_interval_sec: 0.0, ..Settings::default() };
+   /// let mut lane = ThreadProfile::new(None, "main".to_string());
+   ///
+   /// for _ in 0..6 {
+   ///    lane.time_event_start(&settings, "FRAME").unwrap();
+   ///    for (parent, ms) in [("LOAD", 1), ("DRAW", 3)] {
+   ///       lane.time_event_start(&settings, parent).unwrap();
+   ///       lane.time_event_start(&settings, "WORK").unwrap();
+   ///       sleep(Duration::from_millis(ms));
+   ///       lane.time_event_end("WORK").unwrap();
+   ///       lane.time_event_end(parent).unwrap();
+   ///    }
+   ///    lane.time_event_end("FRAME").unwrap();
+   /// }
+   ///
+   /// let frame = lane.call_paths.get(None, "FRAME");
+   /// let load_work = lane.call_paths.get(lane.call_paths.get(frame, "LOAD"), "WORK").unwrap();
+   /// let draw_work = lane.call_paths.get(lane.call_paths.get(frame, "DRAW"), "WORK").unwrap();
+   ///
+   /// // one function, two paths timed apart, the name merges both
+   /// let (load, draw) = (lane.pull_path_data(load_work, 1), lane.pull_path_data(draw_work, 1));
+   /// assert!(load >= 1.0 && draw >= 3.0 && load < draw);
+   /// assert!((lane.pull_data("WORK", 1) - (load + draw)).abs() < 1e-9);
+   /// assert!(lane.latest_tree.nodes.contains_key(&load_work) && lane.latest_tree.nodes.contains_key(&draw_work));
+   /// ```
+   pub fn pull_path_data(&self, path: PathId, smoothing_amount: u32) -> f64 {
+      self.pull_path_statistic(path, Statistic::Mean, smoothing_amount)
+   }
+
+   /// smoothed self time in ms of one call path
+   pub fn pull_path_self_data(&self, path: PathId, smoothing_amount: u32) -> f64 {
+      self.pull_path_statistic(path, Statistic::SelfTime, smoothing_amount)
+   }
+
+   /// a stored frame by its ``CapturedFrame::number``, ``None`` once it's left the buffer
+   pub fn frame(&self, number: u64) -> Option<&CapturedFrame> {
+      let first = self.frames.front()?.number;
+      self.frames.get(number.checked_sub(first)? as usize)
+   }
+
+   /// any statistic of one call path, averaged over the last ``smoothing_amount`` intervals
+   pub fn pull_path_statistic(&self, path: PathId, statistic: Statistic, smoothing_amount: u32) -> f64 {
+      match self.path_profiles.get(path.index()) {
+         None => 0.0,
+         Some(profile) => profile.pull_smoothed(statistic, smoothing_amount),
+      }
+   }
+
+   /// every function on this thread sorted by smoothed self time, highest first
+   pub fn top_self_time(&self, smoothing_amount: u32) -> Vec<(StatString, f64)> {
+      let mut top: Vec<(StatString, f64)> = self.all_profiles.keys()
+          .map(|name| (*name, self.pull_self_data(name, smoothing_amount)))
+          .filter(|(_, time)| time.is_finite())
+          .collect();
+
+      top.sort_by(|one, two| two.1.total_cmp(&one.1));
+      top
+   }
+}
+
+/// names a lane for as long as the program runs, lanes of loaded sessions have no thread to take an id from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LaneId(u64);
+impl LaneId {
+   fn next() -> Self {
+      static NEXT: AtomicU64 = AtomicU64::new(0);
+      Self(NEXT.fetch_add(1, Ordering::Relaxed))
+   }
+}
+
+/// an event that hasn't ended yet
+#[derive(Debug)]
+struct OpenSpan {
+   /// index into ``ThreadProfile::active_frame.spans``
+   index: usize,
+
+   path: PathId,
+
+   /// summed time of the child events that already ended
+   children: f64,
+}
+
+/// oldest warnings are dropped past this
+const MAX_WARNINGS: usize = 16;
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::RwLock;
 use std::thread;
 
@@ -6,36 +7,17 @@ use egui::{CentralPanel, Context};
 use crate::{open_profiler, PerformanceProfiler};
 
 pub fn spawn_disconnected_window(prof: &'static RwLock<PerformanceProfiler>) {
-   println!("pre");
-
    let app = DisplayApp {
       prof,
    };
 
-   struct DisplayApp {
-      prof: &'static RwLock<PerformanceProfiler>,
-   }
-   impl eframe::App for DisplayApp {
-      fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
-         CentralPanel::default()
-             .show(ctx, |ui| {
-                open_profiler(self.prof, |mut p| p.handy_performance_benchmarking_ui_section_with_cool_looking_graphs_and_knobs_and_things_and_stuff_looks_very_cool(ui));
-             });
-
-         ctx.request_repaint();
-      }
-   }
-
    let _ = thread::Builder::new()
        .name("ProfilerWindowThread".to_string())
        .stack_size(4 * 1024 * 1024) // 4 MB stack size
        .spawn(|| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
           let native_options = eframe::NativeOptions {
              vsync: true,
-             event_loop_builder: Some(Box::new(|builder: &mut EventLoopBuilder<UserEvent>| {
-                use winit::platform::windows::EventLoopBuilderExtWindows;
-                builder.with_any_thread(true);
-             })),
+             event_loop_builder: Some(Box::new(allow_any_thread)),
              viewport: egui::ViewportBuilder::default()
                  .with_inner_size([400.0, 300.0])
                  .with_min_inner_size([300.0, 220.0]),
@@ -49,6 +31,66 @@ pub fn spawn_disconnected_window(prof: &'static RwLock<PerformanceProfiler>) {
           ).expect("failed to run");
           Ok(())
        });
+}
+
+/// runs the ui in a window on the calling thread until it's closed, for the main thread of a viewer,
+/// apps with a window of their own use ``spawn_disconnected_window`` instead
+pub fn run_window(prof: &'static RwLock<PerformanceProfiler>, title: &str) -> eframe::Result<()> {
+   let native_options = eframe::NativeOptions {
+      vsync: true,
+      viewport: egui::ViewportBuilder::default()
+          .with_inner_size([1000.0, 700.0])
+          .with_min_inner_size([300.0, 220.0]),
+      ..Default::default()
+   };
+
+   eframe::run_native(
+      title,
+      native_options,
+      Box::new(move |_cc| Ok(Box::new(DisplayApp { prof }))),
+   )
+}
+
+struct DisplayApp {
+   prof: &'static RwLock<PerformanceProfiler>,
+}
+impl eframe::App for DisplayApp {
+   fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+      CentralPanel::default()
+          .show(ctx, |ui| {
+             open_profiler(self.prof, |mut p| p.handy_performance_benchmarking_ui_section_with_cool_looking_graphs_and_knobs_and_things_and_stuff_looks_very_cool(ui));
+          });
+
+      ctx.request_repaint();
+   }
+}
+
+/// winit only runs its event loop on the main thread unless told otherwise
+fn allow_any_thread(builder: &mut EventLoopBuilder<UserEvent>) {
+   #[cfg(target_os = "windows")]
+   {
+      use winit::platform::windows::EventLoopBuilderExtWindows;
+      builder.with_any_thread(true);
+   }
+
+   // both backends get it, winit picks one at runtime
+   #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+   {
+      use winit::platform::wayland::EventLoopBuilderExtWayland;
+      use winit::platform::x11::EventLoopBuilderExtX11;
+      EventLoopBuilderExtX11::with_any_thread(builder, true);
+      EventLoopBuilderExtWayland::with_any_thread(builder, true);
+   }
+
+   // macos can't run an event loop off the main thread at all
+   #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+   let _ = builder;
+}
+
+/// opens a session saved with ``PerformanceProfiler::save_session`` in its own window, nothing needs to be profiled
+pub fn spawn_disconnected_session_window(path: impl AsRef<Path>) -> std::io::Result<()> {
+   let prof = PerformanceProfiler::load_session(path)?.into_static();
+   spawn_disconnected_window(prof);
 
-   println!("post");
-}
\ No newline at end of file
+   Ok(())
+}
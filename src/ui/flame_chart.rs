@@ -0,0 +1,159 @@
+use egui::{Align2, Color32, FontId, Pos2, Response, Rounding, Sense, Stroke, Ui};
+use crate::{CapturedFrame, LaneId, PerformanceProfiler};
+use crate::ui::new_display::{display_segment, rect_from_seg_x, segment_height};
+use crate::ui::user_interface::{rand_color, self_time_color};
+
+
+/// where the flame chart is looking, shared by every thread so lanes stay lined up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimelineView {
+   /// ms since the frame start at the left edge
+   pub offset: f64,
+   /// 1.0 fits the whole frame
+   pub zoom: f64,
+}
+impl Default for TimelineView {
+   fn default() -> Self {
+      Self {
+         offset: 0.0,
+         zoom: 1.0,
+      }
+   }
+}
+
+impl PerformanceProfiler {
+   /// every span of a single frame at its real start time, so gaps between calls show up as empty space,
+   /// uses the frame picked in the frame strip or the latest stored frame,
+   /// scroll to zoom around the pointer, drag to pan, double click to reset
+   pub fn display_flame_chart(&mut self, ui: &mut Ui, thread: LaneId) -> Option<Response> {
+      let frame = match &self.ui_data.selected_frame {
+         Some((t, frame)) if *t == thread => frame.clone(),
+         _ => self.lane(thread)?.frames.back()?.clone(),
+      };
+      if frame.duration() <= 0.0 { return None; }
+
+      let depth = frame.spans.iter().map(|span| span.depth + 1).max().unwrap_or(1);
+
+      let mut target_size = ui.available_size();
+      target_size.y = (segment_height(ui) * 1.4) * (depth + 1) as f32;
+
+      let (widget_rect, response) = ui.allocate_exact_size(target_size, Sense::click_and_drag());
+
+      // input
+      let view = &mut self.ui_data.timeline_view;
+      let window = frame.duration() / view.zoom;
+
+      if ui.rect_contains_pointer(widget_rect) {
+         let (scroll, pointer, delta, dragging) = ui.input(|i| (
+            i.smooth_scroll_delta.y,
+            i.pointer.hover_pos(),
+            i.pointer.delta(),
+            i.pointer.primary_down(),
+         ));
+
+         if scroll != 0.0 {
+            let anchor = pointer.map(|p| ((p.x - widget_rect.min.x) / widget_rect.width()) as f64).unwrap_or(0.5);
+            let anchor_ms = view.offset + anchor * window;
+
+            view.zoom = (view.zoom * (scroll as f64 * ZOOM_SPEED).exp()).clamp(1.0, MAX_ZOOM);
+            view.offset = anchor_ms - anchor * frame.duration() / view.zoom;
+         }
+
+         if dragging {
+            view.offset -= delta.x as f64 / widget_rect.width() as f64 * window;
+         }
+      }
+
+      if response.double_clicked() {
+         *view = TimelineView::default();
+      }
+
+      let window = frame.duration() / view.zoom;
+      view.offset = view.offset.clamp(0.0, frame.duration() - window);
+      let view = *view;
+
+      // draw
+      ui.painter().rect(widget_rect, Rounding::ZERO, Color32::BLACK, Stroke::default());
+
+      if !ui.is_rect_visible(widget_rect) { return Some(response); }
+
+      let segmentation = widget_rect.height() / (depth + 1) as f32;
+      let to_x = |ms: f64| ((ms - view.offset) / window) as f32 * widget_rect.width();
+
+      ruler(ui, &frame, view, widget_rect, segmentation);
+
+      let self_times = span_self_times(&frame);
+      let clip = ui.clip_rect();
+      ui.set_clip_rect(widget_rect.intersect(clip));
+
+      for (span, self_time) in frame.spans.iter().zip(self_times) {
+         let start = span.start - frame.start;
+         let end = span.end - frame.start;
+         if end < view.offset || start > view.offset + window { continue; }
+
+         // one row is kept for the ruler
+         let bar_rect = rect_from_seg_x(
+            to_x(start).max(0.0),
+            to_x(end).min(widget_rect.width()),
+            span.depth,
+            segmentation,
+            widget_rect,
+         );
+
+         let color = match self.ui_data.bars_by_self_time {
+            true => self_time_color(self_time, frame.duration()),
+            false => rand_color(span.name),
+         };
+
+         let seg_resp = display_segment(ui, bar_rect, span.name, span.duration() as f32, color);
+
+         self.track_focus(&seg_resp, (thread, span.name));
+      }
+
+      ui.set_clip_rect(clip);
+
+      Some(response)
+   }
+}
+
+/// time since the frame start along the top row
+fn ruler(ui: &mut Ui, frame: &CapturedFrame, view: TimelineView, rect: egui::Rect, segmentation: f32) {
+   let window = frame.duration() / view.zoom;
+   let y = rect.min.y + segmentation / 2.0;
+
+   for tick in 0..=RULER_TICKS {
+      let along = tick as f32 / RULER_TICKS as f32;
+      let ms = view.offset + along as f64 * window;
+      let x = rect.min.x + along * rect.width();
+
+      let align = match tick {
+         0 => Align2::LEFT_CENTER,
+         RULER_TICKS => Align2::RIGHT_CENTER,
+         _ => Align2::CENTER_CENTER,
+      };
+
+      ui.painter().text(Pos2::new(x, y), align, format!("{ms:.2}ms"), RULER_FONT, Color32::GRAY);
+   }
+}
+
+/// each spans duration minus its direct children, spans are in start order so a stack of
+/// open parents is enough
+fn span_self_times(frame: &CapturedFrame) -> Vec<f64> {
+   let mut self_times: Vec<f64> = frame.spans.iter().map(|span| span.duration()).collect();
+   let mut parents: Vec<usize> = vec![];
+
+   for (i, span) in frame.spans.iter().enumerate() {
+      parents.truncate(span.depth as usize);
+      if let Some(parent) = parents.last() {
+         self_times[*parent] -= span.duration();
+      }
+      parents.push(i);
+   }
+
+   self_times
+}
+
+const ZOOM_SPEED: f64 = 0.005;
+const MAX_ZOOM: f64 = 10_000.0;
+const RULER_TICKS: usize = 4;
+const RULER_FONT: FontId = FontId::monospace(10.0);
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use egui::{Color32, Stroke, Ui};
+use egui_plot::{Bar, BarChart, Plot};
+
+use crate::{CapturedFrame, LaneId, PerformanceProfiler, StatString};
+use crate::ui::user_interface::{GenericTreeBarThing, LoneBar};
+
+
+/// scrubbable history of every stored frame
+impl PerformanceProfiler {
+   /// one bar per stored frame of a thread, clicking a bar keeps a copy of that frame in
+   /// ``UiData::selected_frame`` so the tree view shows it instead of the averages
+   pub fn frame_time_strip(&mut self, ui: &mut Ui, thread: LaneId) {
+      let Some(lane) = self.lane(thread) else { return; };
+
+      let selected = match &self.ui_data.selected_frame {
+         Some((t, frame)) if *t == thread => Some(frame.number),
+         _ => None,
+      };
+
+      let bars: Vec<Bar> = lane.frames.iter()
+          .map(|frame| {
+             let color = match Some(frame.number) == selected {
+                true => Color32::WHITE,
+                false => frame_time_color(frame.duration()),
+             };
+
+             Bar::new(frame.number as f64, frame.duration())
+                 .width(0.9)
+                 .name(format!("frame {}", frame.number))
+                 .fill(color)
+                 .stroke(Stroke::NONE)
+          })
+          .collect();
+
+      let plot = Plot::new(("Frame strip", thread))
+          .height(STRIP_HEIGHT)
+          .show_grid([false, true])
+          .show_axes([false, true])
+          .allow_scroll(false)
+          .allow_zoom(false)
+          .allow_boxed_zoom(false)
+          .allow_drag(false)
+          .include_y(0.0);
+
+      let clicked = plot.show(ui, |plot_ui| {
+         plot_ui.bar_chart(BarChart::new(bars));
+
+         match plot_ui.response().clicked() {
+            true => plot_ui.pointer_coordinate().map(|pos| pos.x.round() as u64),
+            false => None,
+         }
+      }).inner;
+
+      if let Some(frame) = clicked.and_then(|number| lane.frame(number)) {
+         self.ui_data.selected_frame = Some((thread, frame.clone()));
+      }
+   }
+
+   /// bars for the frame picked in the strip, ``None`` if no frame of ``thread`` is picked
+   pub fn generate_selected_frame_tree_bars(&self, thread: LaneId) -> Option<GenericTreeBarThing> {
+      match &self.ui_data.selected_frame {
+         Some((t, frame)) if *t == thread => Some(frame_tree_bars(frame)),
+         _ => None,
+      }
+   }
+}
+
+/// the exact times of a single frame laid out like the averaged tree, repeat calls of the same
+/// call path are summed, events without a common outermost event go under a synthetic frame bar
+pub fn frame_tree_bars(frame: &CapturedFrame) -> GenericTreeBarThing {
+   let mut nodes: Vec<FrameNode> = vec![];
+   let mut ids: HashMap<(Option<usize>, StatString), usize> = HashMap::new();
+   let mut roots = vec![];
+   let mut stack: Vec<usize> = vec![];
+
+   for span in frame.spans.iter() {
+      stack.truncate(span.depth as usize);
+      let parent = stack.last().copied();
+
+      let id = *ids.entry((parent, span.name)).or_insert_with(|| {
+         nodes.push(FrameNode { name: span.name, time: 0.0, children: vec![] });
+         let id = nodes.len() - 1;
+         match parent {
+            None => roots.push(id),
+            Some(parent) => nodes[parent].children.push(id),
+         }
+         id
+      });
+
+      nodes[id].time += span.duration();
+      stack.push(id);
+   }
+
+   let mut tree = GenericTreeBarThing::new();
+
+   match roots.as_slice() {
+      [root] => push_frame_node(&nodes, *root, 0, 0.0, &mut tree),
+      _ => {
+         let children_time: f64 = roots.iter().map(|root| nodes[*root].time).sum();
+         tree.push(0, LoneBar {
+            name: FRAME_BAR,
+            time: frame.duration(),
+            self_time: frame.duration() - children_time,
+            positions: [0.0, frame.duration()],
+            diff: None,
+         });
+
+         let mut start_from = 0.0;
+         for root in roots {
+            push_frame_node(&nodes, root, 1, start_from, &mut tree);
+            start_from += nodes[root].time;
+         }
+      }
+   }
+
+   tree
+}
+
+fn push_frame_node(nodes: &[FrameNode], id: usize, depth: usize, start_from: f64, tree: &mut GenericTreeBarThing) {
+   let node = &nodes[id];
+   let children_time: f64 = node.children.iter().map(|child| nodes[*child].time).sum();
+
+   tree.push(depth, LoneBar {
+      name: node.name,
+      time: node.time,
+      self_time: node.time - children_time,
+      positions: [start_from, node.time],
+      diff: None,
+   });
+
+   let mut start_from = start_from;
+   for child in node.children.iter() {
+      push_frame_node(nodes, *child, depth + 1, start_from, tree);
+      start_from += nodes[*child].time;
+   }
+}
+
+/// one call path of a single frame
+struct FrameNode {
+   name: StatString,
+   time: f64,
+   children: Vec<usize>,
+}
+
+/// green at 60fps or better, red at 30fps or worse
+fn frame_time_color(ms: f64) -> Color32 {
+   let t = ((ms - 1000.0 / 60.0) / (1000.0 / 30.0 - 1000.0 / 60.0)).clamp(0.0, 1.0) as f32;
+   Color32::from_rgb((255.0 * t) as u8, (255.0 * (1.0 - t)) as u8, 60)
+}
+
+const STRIP_HEIGHT: f32 = 60.0;
+const FRAME_BAR: StatString = "FRAME";
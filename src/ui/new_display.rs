@@ -1,12 +1,13 @@
-use eframe::epaint::Rect;
-use egui::{Align2, Color32, FontId, Pos2, Response, Rounding, Sense, Stroke, Ui, Vec2};
-use crate::PerformanceProfiler;
-use crate::ui::user_interface::{GenericTreeBarThing, rand_color};
+use egui::{Align2, Color32, FontId, Pos2, Rect, Response, Rounding, Sense, Stroke, Ui, Vec2};
+use crate::{LaneId, PerformanceProfiler};
+use crate::ui::user_interface::GenericTreeBarThing;
 
 impl PerformanceProfiler {
-   pub fn display_new_tree(&mut self, ui: &mut Ui, generic_tree_bar_thing: &mut GenericTreeBarThing) -> Response {
+   pub fn display_new_tree(&mut self, ui: &mut Ui, thread: LaneId, generic_tree_bar_thing: &mut GenericTreeBarThing) -> Response {
       // setup
-      self.ui_data.last_hovered_profile_tree = None;
+      if self.ui_data.last_hovered_profile_tree.is_some_and(|(t, _)| t == thread) {
+         self.ui_data.last_hovered_profile_tree = None;
+      }
 
       let mut target_size = ui.available_size();
       target_size.y = (segment_height(ui) * 1.4) * generic_tree_bar_thing.layers.len() as f32;
@@ -20,6 +21,9 @@ impl PerformanceProfiler {
       // draw
       ui.painter().rect(widget_rect, Rounding::ZERO, Color32::BLACK, Stroke::default());
 
+      let root_time = generic_tree_bar_thing.root_time();
+      let by_self_time = self.ui_data.bars_by_self_time;
+
       generic_tree_bar_thing.normalize();
       generic_tree_bar_thing.sort_layers();
       let depth = generic_tree_bar_thing.layers.len();
@@ -31,7 +35,7 @@ impl PerformanceProfiler {
          let segmentation = widget_rect.height() / depth as f32;
 
          for (depth, layer) in generic_tree_bar_thing.layers.iter().enumerate() {
-            for (_layer_index, bar) in layer.iter().enumerate() {
+            for bar in layer.iter() {
                let bar_rect = rect_from_seg_x(
                   bar.positions[0] as f32 * rect.width(),
                   (bar.positions[0] + bar.positions[1]) as f32 * rect.width(),
@@ -40,26 +44,21 @@ impl PerformanceProfiler {
                   rect,
                );
 
+               let time = match by_self_time {
+                  true => bar.self_time,
+                  false => bar.time,
+               };
+               let color = self.bar_color(bar, root_time);
+
                let seg_resp = display_segment(
                   ui,
                   bar_rect,
                   bar.name,
-                  bar.time as f32,
-                  rand_color(bar.name),
+                  time as f32,
+                  color,
                );
 
-               if seg_resp.hovered() {
-                  self.ui_data.last_hovered_profile_tree = Some(bar.name);
-               }
-
-               if seg_resp.clicked() {
-                  if self.ui_data.focused_profiles.contains(&bar.name) {
-                     self.ui_data.focused_profiles.retain(|b| b != &bar.name);
-                  } else {
-                     self.ui_data.focused_profiles.push(bar.name);
-                  }
-
-               }
+               self.track_focus(&seg_resp, (thread, bar.name));
             }
          }
       }
@@ -70,7 +69,7 @@ impl PerformanceProfiler {
 
 
 /// 0 is lowest segment
-fn rect_from_seg_x(
+pub(crate) fn rect_from_seg_x(
    lower_x: f32,
    upper_x: f32,
    segment: u32,
@@ -83,7 +82,7 @@ fn rect_from_seg_x(
    }
 }
 
-fn segment_height(ui: &mut Ui) -> f32 {
+pub(crate) fn segment_height(ui: &mut Ui) -> f32 {
    let one_char_size = ui.painter().layout_no_wrap("P".to_string(), FONT, Color32::PLACEHOLDER).size();
    one_char_size.y + HEIGHT_BUFFER
 }
@@ -103,7 +102,7 @@ const MS_SPACING: usize = 2;
 const LEFT_BUFFER: f32 = 7.5;
 const TEXT_SIZE: f32 = 11.5;
 const MIN_WIDTH: f32 = 1.5;
-fn display_segment(
+pub(crate) fn display_segment(
    ui: &mut Ui,
    rect: Rect,
    name: &str,
@@ -1,41 +1,88 @@
 use std::hash::{DefaultHasher, Hash, Hasher};
 
-use egui::{Context, Stroke, TextStyle, WidgetText, Window};
-use egui::{CollapsingHeader, Color32, DragValue, menu, ScrollArea, Ui};
-use egui_plot::{Bar, BarChart, Corner, Legend, Line, Plot, PlotPoint};
+use egui::{Context, Response, Stroke, TextStyle, WidgetText, Window};
+use egui::{CollapsingHeader, Color32, ComboBox, DragValue, menu, ScrollArea, Ui};
+use egui_plot::{Bar, BarChart, Corner, Legend, Line, Plot, PlotPoint, Polygon};
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
-use crate::profiler::PerformanceProfiler;
+use crate::captured_frame::CapturedFrame;
+use crate::compare::FunctionDiff;
+use crate::function_profile::{FunctionProfile, get_ct, Statistic};
+use crate::profiler::{PerformanceProfiler, Settings};
 use crate::StatString;
+use crate::thread_profile::{LaneId, ThreadProfile};
+use crate::ui::flame_chart::TimelineView;
+use crate::utils::tree::PathId;
 use crate::utils::ui_modules::ToggleSwitch;
 
 /// data held by the ui for
 /// ``PerformanceProfiler::handy_performance_benchmarking_ui_section_with_cool_looking_graphs_and_knobs_and_things_and_stuff_looks_very_cool()``
 #[derive(Debug)]
 pub struct UiData {
-   pub last_hovered_profile_tree: Option<StatString>,
-   pub focused_profiles: Vec<StatString>,
-   pub tree_or_list: bool,
+   pub last_hovered_profile_tree: Option<(LaneId, StatString)>,
+   pub focused_profiles: Vec<(LaneId, StatString)>,
+   /// thread shown in the dropdown, ``None`` falls back to the first thread
+   pub selected_thread: Option<LaneId>,
+   /// what the left panel lists
+   pub left_panel: LeftPanel,
    pub graph_included_upper_ms: f64,
    pub graph_included_upper_fps: f64,
    pub zoom_graph: bool,
+   /// value plotted for each focused function
+   pub graph_statistic: Statistic,
+   /// shades the min to max range behind each focused line
+   pub graph_min_max_band: bool,
+   /// tree bars show self time and are colored by how much of the frame it takes
+   pub bars_by_self_time: bool,
    pub use_new_tree: bool,
+   /// a copy of the frame clicked in the frame strip, shown in the tree instead of the averages
+   pub selected_frame: Option<(LaneId, CapturedFrame)>,
+   /// shows each frames spans at their real start times under the tree
+   pub show_timeline: bool,
+   pub timeline_view: TimelineView,
+   /// tree bars are colored by how they changed against ``PerformanceProfiler::baseline``
+   pub bars_by_diff: bool,
+   /// file name written by the export menu, each format adds its own extension
+   pub export_path: String,
+   pub last_export: Option<Result<String, String>>,
 }
 impl Default for UiData {
    fn default() -> Self {
       Self {
          last_hovered_profile_tree: None,
          focused_profiles: vec![],
-         tree_or_list: true,
+         selected_thread: None,
+         left_panel: LeftPanel::Tree,
          graph_included_upper_ms: 0.0,
          graph_included_upper_fps: 0.0,
          zoom_graph: false,
+         graph_statistic: Statistic::Mean,
+         graph_min_max_band: false,
+         bars_by_self_time: false,
          use_new_tree: true,
+         selected_frame: None,
+         show_timeline: false,
+         bars_by_diff: false,
+         timeline_view: TimelineView::default(),
+         export_path: "triglyceride_capture".to_string(),
+         last_export: None,
       }
    }
 }
 
+/// views of the selected thread shown left of the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftPanel {
+   /// call tree, a function called from two parents shows up under both
+   Tree,
+   /// every call path of a function merged into one entry
+   ByFunction,
+   TopSelfTime,
+   /// every function against the baseline
+   Compare,
+}
+
 
 /// main display
 impl PerformanceProfiler {
@@ -49,12 +96,19 @@ impl PerformanceProfiler {
                ui.label("On/Off")
             });
 
+            ui.horizontal(|ui| {
+               ui.add(ToggleSwitch::new(&mut settings.paused));
+               ui.label("Pause")
+            });
+
             ui.menu_button("Settings", |ui| {
                ui.add(DragValue::new(&mut settings.update_interval_sec).speed(0.01).range(0.0..=f32::MAX).prefix("Data Update Interval -> ").suffix(" sec"));
                ui.add(DragValue::new(&mut settings.stored_cash_amount).speed(0.1).range(3..=200).prefix("Data averaging cash -> "));
                ui.add(DragValue::new(&mut settings.stored_data_amount).speed(0.5).range(1..=u32::MAX).prefix("stored datapoint's for graph -> "));
                ui.add(DragValue::new(&mut self.ui_data.graph_included_upper_ms).speed(1.0).range(0.0..=f64::MAX).prefix("Included upper milliseconds -> "));
                ui.add(DragValue::new(&mut settings.smoothing_amount).speed(0.1).range(0..=u32::MAX).prefix("Tree smoothing amount -> "));
+               ui.add(DragValue::new(&mut settings.stored_frame_amount).speed(0.5).range(1..=u32::MAX).prefix("Stored frames -> "));
+               ui.add(DragValue::new(&mut settings.spike_threshold_ms).speed(0.1).range(0.0..=f64::MAX).prefix("Pause on frames over -> ").suffix(" ms (0 is off)"));
                ui.checkbox(&mut self.ui_data.use_new_tree, "Use New Tree");
             });
 
@@ -68,6 +122,95 @@ impl PerformanceProfiler {
                }
             }
 
+            ui.menu_button("Export", |ui| {
+               ui.horizontal(|ui| {
+                  ui.label("Path");
+                  ui.text_edit_singleline(&mut self.ui_data.export_path);
+               });
+
+               if ui.button("Chrome trace").clicked() {
+                  let path = format!("{}.json", self.ui_data.export_path);
+                  let result = self.export_chrome_trace(&path);
+                  self.ui_data.last_export = Some(export_message(path, result));
+               }
+
+               if ui.button("Folded stacks").clicked() {
+                  let path = format!("{}.folded", self.ui_data.export_path);
+                  let result = self.export_folded_stacks(&path);
+                  self.ui_data.last_export = Some(export_message(path, result));
+               }
+
+               if ui.button("Speedscope").clicked() {
+                  let path = format!("{}.speedscope.json", self.ui_data.export_path);
+                  let result = self.export_speedscope(&path);
+                  self.ui_data.last_export = Some(export_message(path, result));
+               }
+
+               match &self.ui_data.last_export {
+                  None => {}
+                  Some(Ok(message)) => { ui.label(message); }
+                  Some(Err(message)) => { ui.colored_label(Color32::RED, message); }
+               }
+            });
+
+            ui.menu_button("Session", |ui| {
+               ui.horizontal(|ui| {
+                  ui.label("Path");
+                  ui.text_edit_singleline(&mut self.ui_data.export_path);
+               });
+
+               let path = format!("{}.trisession", self.ui_data.export_path);
+
+               if ui.button("Save").clicked() {
+                  let result = self.save_session(&path);
+                  self.ui_data.last_export = Some(export_message(path.clone(), result));
+               }
+
+               // the loaded threads replace the live ones, pausing keeps the live threads from adding new data
+               if ui.button("Load").clicked() {
+                  self.ui_data.last_export = Some(match PerformanceProfiler::load_session(&path) {
+                     Err(e) => Err(format!("Load failed => {e}")),
+                     Ok(loaded) => {
+                        self.threads = loaded.threads;
+                        self.settings = Settings { active: self.settings.active, paused: true, ..loaded.settings };
+                        self.ui_data.selected_thread = None;
+                        self.ui_data.selected_frame = None;
+                        Ok(format!("Loaded {path}"))
+                     }
+                  });
+               }
+
+               ui.separator();
+
+               if ui.button("Load as baseline").clicked() {
+                  self.ui_data.last_export = Some(match PerformanceProfiler::load_session(&path) {
+                     Err(e) => Err(format!("Load failed => {e}")),
+                     Ok(loaded) => {
+                        self.baseline = Some(Box::new(loaded));
+                        Ok(format!("Loaded {path} as baseline"))
+                     }
+                  });
+               }
+
+               if ui.button("Use current as baseline").clicked() {
+                  self.ui_data.last_export = Some(match self.set_baseline_from_live() {
+                     Err(e) => Err(format!("Baseline failed => {e}")),
+                     Ok(()) => Ok("Current data is the baseline".to_string()),
+                  });
+               }
+
+               if self.baseline.is_some() && ui.button("Clear baseline").clicked() {
+                  self.baseline = None;
+                  self.ui_data.bars_by_diff = false;
+               }
+
+               match &self.ui_data.last_export {
+                  None => {}
+                  Some(Ok(message)) => { ui.label(message); }
+                  Some(Err(message)) => { ui.colored_label(Color32::RED, message); }
+               }
+            });
+
             ui.menu_button("Help", |ui| {
                ui.label("Imagine some helpful words")
             });
@@ -77,9 +220,57 @@ impl PerformanceProfiler {
                ui.label("Zoom Graph")
             });
 
-            if let Some(root) = self.latest_tree.root {
-               ui.label(format!("Overall => {:.2}fps", 1.0 / (self.all_profiles[root].pull_latest() / 1000.0)));
+            ui.horizontal(|ui| {
+               ui.add(ToggleSwitch::new(&mut self.ui_data.bars_by_self_time));
+               ui.label("Self Time")
+            });
+
+            ui.horizontal(|ui| {
+               ui.add(ToggleSwitch::new(&mut self.ui_data.show_timeline));
+               ui.label("Timeline")
+            });
+
+            if self.baseline.is_some() {
+               ui.horizontal(|ui| {
+                  ui.add(ToggleSwitch::new(&mut self.ui_data.bars_by_diff));
+                  ui.label("Diff")
+               });
             }
+
+            ComboBox::from_id_salt("Graph statistic")
+                .selected_text(self.ui_data.graph_statistic.label())
+                .show_ui(ui, |ui| {
+                   for statistic in Statistic::ALL {
+                      ui.selectable_value(&mut self.ui_data.graph_statistic, statistic, statistic.label());
+                   }
+                   ui.separator();
+                   ui.checkbox(&mut self.ui_data.graph_min_max_band, "Min/Max band");
+                });
+
+            if self.threads.len() > 1 {
+               let selected = self.selected_thread().map(|lane| lane.name.clone()).unwrap_or_default();
+               ComboBox::from_id_salt("Thread select")
+                   .selected_text(selected)
+                   .show_ui(ui, |ui| {
+                      for lane in self.threads.iter() {
+                         ui.selectable_value(&mut self.ui_data.selected_thread, Some(lane.id), &lane.name);
+                      }
+                   });
+            }
+
+            if let Some(lane) = self.selected_thread() {
+               if let Some(root) = lane.latest_tree.root {
+                  ui.label(format!("Overall => {:.2}fps", 1.0 / (lane.pull_path_data(root, 1) / 1000.0)));
+               }
+            }
+
+            let overhead = self.overhead();
+            ui.label(format!("Profiler {:.2}%", overhead.percent()))
+                .on_hover_text(format!(
+                   "ms per second spent on the profiler itself\n\
+                   lock waits {:.3}\nevents and trees {:.3}\nresolving {:.3}\nui {:.3}",
+                   overhead.lock_wait, overhead.events, overhead.resolve, overhead.ui,
+                ));
          });
       });
    }
@@ -87,8 +278,11 @@ impl PerformanceProfiler {
    /// a cool looking ui for all the collected statistics, can be used inside any egui container, e.g. window or central panel,
    /// name is WIP
    pub fn handy_performance_benchmarking_ui_section_with_cool_looking_graphs_and_knobs_and_things_and_stuff_looks_very_cool(&mut self, ui: &mut Ui) {
+      let start = get_ct();
+
       // menubar
       self.menubar(ui);
+      self.warning_banner(ui);
 
       let mw = ui.available_width();
       let hw = ui.available_height();
@@ -99,7 +293,19 @@ impl PerformanceProfiler {
 
             ui.group(|ui| {
                ui.set_max_width(mw * 0.25);
-               self.simple_function_tree_dropdown(ui);
+               ui.horizontal(|ui| {
+                  ui.selectable_value(&mut self.ui_data.left_panel, LeftPanel::Tree, "Tree");
+                  ui.selectable_value(&mut self.ui_data.left_panel, LeftPanel::ByFunction, "By function");
+                  ui.selectable_value(&mut self.ui_data.left_panel, LeftPanel::TopSelfTime, "Top self time");
+                  ui.selectable_value(&mut self.ui_data.left_panel, LeftPanel::Compare, "Compare");
+               });
+
+               match self.ui_data.left_panel {
+                  LeftPanel::Tree => self.simple_function_tree_dropdown(ui),
+                  LeftPanel::ByFunction => self.by_function_list(ui),
+                  LeftPanel::TopSelfTime => self.top_self_time_list(ui),
+                  LeftPanel::Compare => self.compare_list(ui),
+               }
             });
 
 
@@ -109,20 +315,86 @@ impl PerformanceProfiler {
          });
 
          ui.group(|ui| {
-            match self.ui_data.use_new_tree {
-               true => {
-                  if let Some(root) = self.latest_tree.root {
-                     let mut tree = self.generate_generic_tree_bars(root);
-                     self.display_new_tree(ui, &mut tree);
+            let lanes: Vec<(LaneId, String)> = self.threads.iter()
+                .map(|lane| (lane.id, lane.name.clone()))
+                .collect();
+            let show_names = lanes.len() > 1;
+
+            for (id, name) in lanes {
+               if show_names {
+                  ui.label(name);
+               }
+
+               self.frame_time_strip(ui, id);
+
+               if let Some((_, frame)) = self.ui_data.selected_frame.as_ref().filter(|(t, _)| *t == id) {
+                  let text = format!("Frame {} => {}", frame.number, show_time(frame.duration()));
+                  ui.horizontal(|ui| {
+                     ui.label(text);
+                     if ui.button("Live").clicked() {
+                        self.ui_data.selected_frame = None;
+                     }
+                  });
+               }
+
+               let tree = self.generate_selected_frame_tree_bars(id)
+                   .or_else(|| self.generate_generic_tree_bars(id));
+
+               if let Some(mut tree) = tree {
+                  match self.ui_data.use_new_tree {
+                     true => { self.display_new_tree(ui, id, &mut tree); }
+                     false => self.display_egui_plot_of_generic_tree_bars(ui, id, &tree),
                   }
                }
-               false => {
-                  self.tree_bar_chart(ui);
+
+               if self.ui_data.show_timeline {
+                  self.display_flame_chart(ui, id);
                }
             }
-
          });
       });
+
+      self.overhead.current.ui += get_ct() - start;
+   }
+
+   /// lists every threads instrumentation errors and the profilers own warnings until dismissed
+   fn warning_banner(&mut self, ui: &mut Ui) {
+      if self.warnings.is_empty() && self.threads.iter().all(|lane| lane.warnings.is_empty()) { return; }
+
+      ui.group(|ui| {
+         for warning in self.warnings.iter() {
+            ui.colored_label(Color32::YELLOW, format!("⚠ {warning}"));
+         }
+         for lane in self.threads.iter() {
+            for warning in lane.warnings.iter() {
+               ui.colored_label(Color32::YELLOW, format!("⚠ {} => {warning}", lane.name));
+            }
+         }
+
+         if ui.button("Dismiss").clicked() {
+            self.warnings.clear();
+            for lane in self.threads.iter_mut() {
+               lane.warnings.clear();
+            }
+         }
+      });
+   }
+
+   /// the thread picked in the menubar, or the first thread to time anything
+   pub fn selected_thread(&self) -> Option<&ThreadProfile> {
+      match self.ui_data.selected_thread.and_then(|id| self.lane(id)) {
+         None => self.threads.first(),
+         Some(lane) => Some(lane),
+      }
+   }
+
+   /// fill of a tree bar for the current coloring toggles
+   pub fn bar_color(&self, bar: &LoneBar, root_time: f64) -> Color32 {
+      match (bar.diff, self.ui_data.bars_by_self_time) {
+         (Some(diff), _) => diff_color(&diff),
+         (None, true) => self_time_color(bar.self_time, root_time),
+         (None, false) => rand_color(bar.name),
+      }
    }
 
    pub fn display_floating_window(&mut self, ctx: &Context) {
@@ -142,60 +414,187 @@ impl PerformanceProfiler {
          ScrollArea::vertical()
              .id_salt("List all functions")
              .show(ui, |ui| {
-                for (name, profile) in self.all_profiles.iter() {
-                   ui.horizontal(|ui| {
-                      ui.label(format!("{} => {name}", show_time(profile.pull_latest())));
-                   });
-                };
+                for lane in self.threads.iter() {
+                   for (name, profile) in lane.all_profiles.iter() {
+                      ui.horizontal(|ui| {
+                         ui.label(format!("{}{} => {name} [{}]", show_time(profile.pull_latest()), show_calls(profile), lane.name));
+                      });
+                   };
+                }
              });
       });
    }
 
 
-   fn name_string_to_text(&self, name: StatString, time: f64) -> WidgetText {
-      let mut t = WidgetText::from(format!("{name} => {}", show_time(time)));
+   fn name_string_to_text(&self, lane: &ThreadProfile, path: PathId, time: f64) -> WidgetText {
+      let thread = lane.id;
+      let name = lane.call_paths.name(path);
+      let calls = lane.path_profiles.get(path.index()).map(show_calls).unwrap_or_default();
+      let mut t = WidgetText::from(format!("{name} => {}{calls}", show_time(time)));
 
-      if self.ui_data.focused_profiles.contains(&name) {
+      if self.ui_data.focused_profiles.contains(&(thread, name)) {
          t = t.underline();
       };
 
-      if let Some(n) = self.ui_data.last_hovered_profile_tree {
-         if n == name {
-            t = t.strong();
-         }
+      if self.ui_data.last_hovered_profile_tree == Some((thread, name)) {
+         t = t.strong();
       }
 
       t
    }
 
-   fn recursive_dropdown_of_children(&self, name: StatString, ui: &mut Ui) {
-      let self_ms = self.all_profiles.get(name).unwrap().pull_latest();
-      let children = &self.latest_tree.nodes.get(name).unwrap().children;
-      let text = self.name_string_to_text(name, self_ms);
+   fn recursive_dropdown_of_children(&self, lane: &ThreadProfile, path: PathId, ui: &mut Ui) {
+      let self_ms = lane.pull_path_data(path, 1);
+      let text = self.name_string_to_text(lane, path, self_ms);
+      let Some(node) = lane.latest_tree.nodes.get(&path) else {
+         ui.label(text);
+         return;
+      };
+      let children = &node.children;
       match children.is_empty() {
          true => { ui.label(text); }
          false => {
-            CollapsingHeader::new(text).id_salt(name).show(ui, |ui| {
+            CollapsingHeader::new(text).id_salt((lane.id, path)).show(ui, |ui| {
                let mut child_tot = 0.0;
                for child in children.iter() {
-                  self.recursive_dropdown_of_children(child, ui);
-                  child_tot += self.all_profiles.get(child).unwrap().pull_latest()
+                  self.recursive_dropdown_of_children(lane, *child, ui);
+                  child_tot += lane.pull_path_data(*child, 1);
                }
                ui.label(format!(".. => {}", show_time(self_ms - child_tot)))
             });
          }
       };
    }
+   /// the selected thread against the same thread of the baseline, regressions in red, click to focus
+   pub fn compare_list(&mut self, ui: &mut Ui) {
+      let Some(lane) = self.selected_thread() else {
+         ui.label("No threads detected");
+         return;
+      };
+      let Some(baseline) = self.baseline_thread(lane) else {
+         ui.label(match self.baseline {
+            None => "No baseline, load or set one in the Session menu",
+            Some(_) => "Thread isn't in the baseline",
+         });
+         return;
+      };
+
+      let thread = lane.id;
+      let diffs = lane.compare_functions(baseline, self.settings.smoothing_amount);
+
+      ui.group(|ui| {
+         ScrollArea::vertical()
+             .auto_shrink([true, true])
+             .id_salt("Compare")
+             .show(ui, |ui| {
+                for diff in diffs {
+                   let side = |time: Option<f64>| time.map(show_time).unwrap_or_else(|| "-".to_string());
+                   let text = format!(
+                      "{} => {} -> {} ({:+.2}ms, {:+.1}%)",
+                      diff.name, side(diff.baseline), side(diff.current), diff.delta, diff.percent,
+                   );
+
+                   let mut text = WidgetText::from(text);
+                   if diff.is_regression() {
+                      text = text.color(Color32::RED);
+                   } else if diff.is_improvement() {
+                      text = text.color(Color32::GREEN);
+                   }
+
+                   self.focusable_row(ui, (thread, diff.name), text);
+                }
+             });
+      });
+   }
+
+   /// internal function, a selectable row for a function, see ``track_focus``
+   fn focusable_row(&mut self, ui: &mut Ui, key: (LaneId, StatString), text: impl Into<WidgetText>) {
+      let focused = self.ui_data.focused_profiles.contains(&key);
+      let response = ui.selectable_label(focused, text);
+      self.track_focus(&response, key);
+   }
+
+   /// internal function, hovering a function highlights it in the plots, clicking toggles it in ``focused_profiles``
+   pub(crate) fn track_focus(&mut self, response: &Response, key: (LaneId, StatString)) {
+      if response.hovered() {
+         self.ui_data.last_hovered_profile_tree = Some(key);
+      }
+
+      if response.clicked() {
+         match self.ui_data.focused_profiles.contains(&key) {
+            true => self.ui_data.focused_profiles.retain(|k| k != &key),
+            false => self.ui_data.focused_profiles.push(key),
+         }
+      }
+   }
+
+   /// the selected threads functions with every call path merged, ordered by time per frame, click to focus
+   pub fn by_function_list(&mut self, ui: &mut Ui) {
+      let Some(lane) = self.selected_thread() else {
+         ui.label("No threads detected");
+         return;
+      };
+
+      let thread = lane.id;
+      let smoothing = self.settings.smoothing_amount;
+      let mut functions: Vec<(StatString, f64, String)> = lane.all_profiles.iter()
+          .map(|(name, profile)| (*name, lane.pull_data(name, smoothing), show_calls(profile)))
+          .collect();
+      functions.sort_by(|one, two| two.1.total_cmp(&one.1));
+
+      ui.group(|ui| {
+         ScrollArea::vertical()
+             .auto_shrink([true, true])
+             .id_salt("By function")
+             .show(ui, |ui| {
+                for (name, ms, calls) in functions {
+                   self.focusable_row(ui, (thread, name), format!("{name} => {}{calls}", show_time(ms)));
+                }
+             });
+      });
+   }
+
+   /// the selected threads functions ordered by self time, so hot leaf work is obvious, click to focus
+   pub fn top_self_time_list(&mut self, ui: &mut Ui) {
+      let Some(lane) = self.selected_thread() else {
+         ui.label("No threads detected");
+         return;
+      };
+
+      let thread = lane.id;
+      let top = lane.top_self_time(self.settings.smoothing_amount);
+      let frame_ms = lane.latest_tree.root.map(|root| lane.pull_path_data(root, self.settings.smoothing_amount));
+
+      ui.group(|ui| {
+         ScrollArea::vertical()
+             .auto_shrink([true, true])
+             .id_salt("Top self time")
+             .show(ui, |ui| {
+                for (name, self_ms) in top {
+                   let share = match frame_ms {
+                      Some(frame_ms) if frame_ms > 0.0 => format!(" ({:.1}%)", self_ms / frame_ms * 100.0),
+                      _ => String::new(),
+                   };
+
+                   self.focusable_row(ui, (thread, name), format!("{name} => {}{share}", show_time(self_ms)));
+                }
+             });
+      });
+   }
+
    pub fn simple_function_tree_dropdown(&mut self, ui: &mut Ui) {
       ui.group(|ui| {
          ScrollArea::vertical()
              .auto_shrink([true, true])
              .id_salt("Simple dropdown")
              .show(ui, |ui| {
-                match self.latest_tree.root {
-                   None => { ui.label("No root node detected"); }
-                   Some(root) => {
-                      self.recursive_dropdown_of_children(root, ui);
+                match self.selected_thread() {
+                   None => { ui.label("No threads detected"); }
+                   Some(lane) => match lane.latest_tree.root {
+                      None => { ui.label("No root node detected"); }
+                      Some(root) => {
+                         self.recursive_dropdown_of_children(lane, root, ui);
+                      }
                    }
                 };
              });
@@ -206,26 +605,8 @@ impl PerformanceProfiler {
 
 /// tree
 impl PerformanceProfiler {
-   fn pull_data(&self, node: StatString) -> f64 {
-      if self.settings.smoothing_amount <= 1 {
-         self.all_profiles.get(node).unwrap().pull_latest()
-      } else {
-         let timeings = &self.all_profiles.get(node).unwrap().timings;
-         let mut c = 0;
-         let mut tot = 0.0;
-         'iter: for i in 0..self.settings.smoothing_amount {
-            if let Some(d) = timeings.get(((timeings.len() - i as usize) as i32 - 1) as usize) {
-               c += 1;
-               tot += d[1];
-            } else { break 'iter; }
-         }
-
-         tot / c as f64
-      }
-   }
-
    /// plots a horizontal (vertical breaks the math for now) barchart, tracks what's hovered / selected in ``self.ui_data``
-   pub fn tree_bar_chart(&mut self, ui: &mut Ui) {
+   pub fn tree_bar_chart(&mut self, ui: &mut Ui, thread: LaneId) {
       // let mut bars: (Vec<Bar>, Vec<StatString>) = (vec![], vec![]);
       //
       // // generate graph
@@ -269,16 +650,16 @@ impl PerformanceProfiler {
 
 
 
-      if let Some(root) = self.latest_tree.root {
-         let tree = self.generate_generic_tree_bars(root);
-         self.display_egui_plot_of_generic_tree_bars(ui, &tree);
+      if let Some(tree) = self.generate_generic_tree_bars(thread) {
+         self.display_egui_plot_of_generic_tree_bars(ui, thread, &tree);
       }
    }
 
    fn generic_recursive_tree(
       &self,
+      lane: &ThreadProfile,
       tree: &mut GenericTreeBarThing,
-      node: StatString,
+      path: PathId,
       depth: usize,
       start_from: f64,
       farthest_depth: &mut usize,
@@ -288,31 +669,48 @@ impl PerformanceProfiler {
          *farthest_depth = depth;
       };
 
-      let node_children = &self.latest_tree.nodes.get(node).unwrap().children;
-      let data = self.pull_data(node);
+      let Some(tree_node) = lane.latest_tree.nodes.get(&path) else { return; };
+      let data = lane.pull_path_data(path, self.settings.smoothing_amount);
 
       tree.push(depth, LoneBar {
-         name: node,
+         name: tree_node.name,
          time: data,
+         self_time: lane.pull_path_self_data(path, self.settings.smoothing_amount),
          positions: [start_from, data],
+         diff: self.tree_diff(lane, path),
       });
 
+      let node_children = &tree_node.children;
+
       if !node_children.is_empty() {
          let mut rcs = start_from;
          for child in node_children.iter() {
-            self.generic_recursive_tree(tree, child, depth + 1, rcs, farthest_depth);
-            rcs += self.pull_data(child);
+            self.generic_recursive_tree(lane, tree, *child, depth + 1, rcs, farthest_depth);
+            rcs += lane.pull_path_data(*child, self.settings.smoothing_amount);
          }
       }
    }
 
-   pub fn generate_generic_tree_bars(&self, root: StatString) -> GenericTreeBarThing {
+   /// the call path against the baseline while the tree is colored by it
+   fn tree_diff(&self, lane: &ThreadProfile, path: PathId) -> Option<FunctionDiff> {
+      if !self.ui_data.bars_by_diff { return None; }
+
+      let baseline = self.baseline_thread(lane)?;
+      Some(lane.compare_path(path, baseline, self.settings.smoothing_amount))
+   }
+
+   /// bars for a threads latest tree, ``None`` if the thread is unknown or has no tree yet
+   pub fn generate_generic_tree_bars(&self, thread: LaneId) -> Option<GenericTreeBarThing> {
+      let lane = self.lane(thread)?;
+      let root = lane.latest_tree.root?;
+
       let mut tree = GenericTreeBarThing::new();
 
       // needed ?
       let mut farthest_depth = 0;
 
       self.generic_recursive_tree(
+         lane,
          &mut tree,
          root,
          0,
@@ -320,23 +718,26 @@ impl PerformanceProfiler {
          &mut farthest_depth,
       );
 
-      tree
+      Some(tree)
    }
 
-   pub fn display_egui_plot_of_generic_tree_bars(&mut self, ui: &mut Ui, tree: &GenericTreeBarThing) {
+   pub fn display_egui_plot_of_generic_tree_bars(&mut self, ui: &mut Ui, thread: LaneId, tree: &GenericTreeBarThing) {
       let mut names = vec![];
       let mut bars = vec![];
+      let root_time = tree.root_time();
 
       for (depth, layer) in tree.layers.iter().enumerate() {
          for bar in layer.iter() {
+            let color = self.bar_color(bar, root_time);
+
             bars.push(
-               bar_from_x_plus(bar.positions[0], bar.positions[1], depth as f64, bar.name)
+               bar_from_x_plus(bar.positions[0], bar.positions[1], depth as f64, bar.name, color)
             );
             names.push(bar.name);
          }
       }
 
-      let plot = Plot::new("Function tree")
+      let plot = Plot::new(("Function tree", thread))
           .show_grid([false, false])
           .show_axes([true, false])
           .allow_scroll(false)
@@ -346,12 +747,14 @@ impl PerformanceProfiler {
       let barchart = BarChart::new(bars.clone());
       plot.show(ui, |plot_ui| {
          plot_ui.bar_chart(barchart);
-         self.ui_data.last_hovered_profile_tree = None;
+         if self.ui_data.last_hovered_profile_tree.is_some_and(|(t, _)| t == thread) {
+            self.ui_data.last_hovered_profile_tree = None;
+         }
 
          if let Some(pos) = plot_ui.pointer_coordinate() {
             for (i, bar) in bars.iter().enumerate() {
                if aabb_collision_check(pos, gen_aabb(bar)) {
-                  let n = names[i];
+                  let n = (thread, names[i]);
                   if plot_ui.response().clicked() {
                      if let Some(index) = self.ui_data.focused_profiles.iter().position(|x| *x == n) {
                         self.ui_data.focused_profiles.remove(index);
@@ -372,9 +775,13 @@ impl PerformanceProfiler {
 pub struct LoneBar {
    pub name: StatString,
    pub time: f64,
+   /// time not spent in child events
+   pub self_time: f64,
    pub positions: [f64; 2],
+   /// against the baseline, only filled in while the tree is colored by it
+   pub diff: Option<FunctionDiff>,
 }
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct GenericTreeBarThing {
    pub layers: Vec<Vec<LoneBar>>,
 }
@@ -403,6 +810,14 @@ impl GenericTreeBarThing {
       }
    }
 
+   /// time of the single root bar, 0 if there isn't exactly one
+   pub fn root_time(&self) -> f64 {
+      match self.layers.first() {
+         Some(layer) if layer.len() == 1 => layer[0].time,
+         _ => 0.0,
+      }
+   }
+
    pub fn normalize(&mut self) -> Option<()> {
       if self.layers.is_empty() { return None; }
       if self.layers[0].len() > 1 { return None; }
@@ -425,23 +840,32 @@ impl GenericTreeBarThing {
 impl PerformanceProfiler {
    pub fn display_graph_of_selected(&mut self, ui: &mut Ui) {
       let mut lines = vec![];
+      let mut bands = vec![];
+      let statistic = self.ui_data.graph_statistic;
 
       // populate lines
       {
-         for focused_profile in self.ui_data.focused_profiles.iter_mut() {
-            let array = &self.all_profiles.get(focused_profile).unwrap().timings;
-            let line = Line::new(array.clone())
-                .color(rand_color(focused_profile))
-                .name(focused_profile);
-            lines.push(line);
+         for (thread, focused_profile) in self.ui_data.focused_profiles.iter() {
+            if let Some(profile) = self.profile_of(*thread, focused_profile) {
+               let color = rand_color(focused_profile);
+               let line = Line::new(profile.series(statistic))
+                   .color(color)
+                   .name(self.line_name(*thread, focused_profile));
+               lines.push(line);
+
+               if self.ui_data.graph_min_max_band {
+                  bands.extend(min_max_band(profile, color));
+               }
+            }
          }
 
-         if let Some(hovered) = self.ui_data.last_hovered_profile_tree {
-            let array = &self.all_profiles.get(hovered).unwrap().timings;
-            let line = Line::new(array.clone())
-                .stroke(Stroke::new(2.0, Color32::WHITE))
-                .name(hovered);
-            lines.push(line);
+         if let Some((thread, hovered)) = self.ui_data.last_hovered_profile_tree {
+            if let Some(profile) = self.profile_of(thread, hovered) {
+               let line = Line::new(profile.series(statistic))
+                   .stroke(Stroke::new(2.0, Color32::WHITE))
+                   .name(self.line_name(thread, hovered));
+               lines.push(line);
+            }
          }
       }
 
@@ -465,12 +889,28 @@ impl PerformanceProfiler {
 
 
       plot.show(ui, |plot_ui| {
+         for band in bands {
+            plot_ui.polygon(band);
+         }
+
          for line in lines {
             plot_ui.line(line);
          }
       },
       );
    }
+
+   fn profile_of(&self, thread: LaneId, name: StatString) -> Option<&FunctionProfile> {
+      self.lane(thread)?.all_profiles.get(name)
+   }
+
+   /// tags the name with its thread once more than one thread is recorded
+   fn line_name(&self, thread: LaneId, name: StatString) -> String {
+      match (self.threads.len() > 1, self.lane(thread)) {
+         (true, Some(lane)) => format!("{name} [{}]", lane.name),
+         _ => name.to_string(),
+      }
+   }
 }
 
 
@@ -480,6 +920,38 @@ fn show_time(t: f64) -> String {
    format!("{t:.2}ms")
 }
 
+/// ``" (x500, 0.01ms/call)"`` for functions called more than once a frame, empty otherwise
+fn show_calls(profile: &FunctionProfile) -> String {
+   match profile.latest_stats() {
+      Some(stats) if stats.calls_per_frame > 1.0 => {
+         format!(" (x{:.0}, {}/call)", stats.calls_per_frame, show_time(stats.mean_per_call))
+      }
+      _ => String::new(),
+   }
+}
+
+fn export_message(path: String, result: std::io::Result<()>) -> Result<String, String> {
+   result
+       .map(|_| format!("Exported to {path}"))
+       .map_err(|e| format!("Export failed => {e}"))
+}
+
+/// one quad per pair of intervals, plot polygons have to be convex so the band can't be a single shape
+fn min_max_band(profile: &FunctionProfile, color: Color32) -> Vec<Polygon> {
+   let min = profile.series(Statistic::Min);
+   let max = profile.series(Statistic::Max);
+
+   min.windows(2)
+       .zip(max.windows(2))
+       .filter(|(low, high)| low.iter().chain(high.iter()).all(|p| p[1].is_finite()))
+       .map(|(low, high)| {
+          Polygon::new(vec![low[0], low[1], high[1], high[0]])
+              .fill_color(color.gamma_multiply(0.3))
+              .stroke(Stroke::NONE)
+       })
+       .collect()
+}
+
 fn gen_aabb(bar: &Bar) -> [PlotPoint; 2] {
    let left_x = bar.base_offset.unwrap();
    let right_x = left_x + bar.value;
@@ -501,13 +973,13 @@ fn aabb_collision_check(point: PlotPoint, aabb: [PlotPoint; 2]) -> bool {
 
 const HEIGHT: f64 = 0.8;
 
-fn bar_from_x_plus(x: f64, plus: f64, height: f64, name: StatString) -> Bar {
+fn bar_from_x_plus(x: f64, plus: f64, height: f64, name: StatString, color: Color32) -> Bar {
    Bar::new(height, plus)
        .horizontal()
        .base_offset(x)
        .width(HEIGHT)
        .name(name)
-       .fill(rand_color(name))
+       .fill(color)
        .stroke(
           Stroke {
              width: 1.0,
@@ -549,6 +1021,36 @@ fn convert_to_color32(color_array: [f64; 4]) -> Color32 {
    Color32::from_rgba_unmultiplied(r, g, b, a)
 }
 
+/// blue for functions that barely cost anything themselves up to red for ones that take the whole frame
+pub fn self_time_color(self_time: f64, root_time: f64) -> Color32 {
+   let heat = match root_time > 0.0 && self_time.is_finite() {
+      true => (self_time / root_time).clamp(0.0, 1.0) as f32,
+      false => 0.0,
+   };
+
+   // most self times are a small share of the frame, stretch the low end
+   let heat = heat.sqrt();
+
+   Color32::from_rgba_unmultiplied(
+      (heat * 255.0) as u8,
+      40,
+      ((1.0 - heat) * 255.0) as u8,
+      128,
+   )
+}
+
+/// red for significant regressions, green for significant improvements, grey for noise
+pub fn diff_color(diff: &FunctionDiff) -> Color32 {
+   let strength = ((diff.percent.abs() / 100.0).clamp(0.0, 1.0) as f32).sqrt();
+   let strength = (80.0 + strength * 175.0) as u8;
+
+   match (diff.is_regression(), diff.is_improvement()) {
+      (true, _) => Color32::from_rgba_unmultiplied(strength, 30, 30, 160),
+      (_, true) => Color32::from_rgba_unmultiplied(30, strength, 30, 160),
+      _ => Color32::from_rgba_unmultiplied(90, 90, 90, 160),
+   }
+}
+
 pub fn rand_color(key: StatString) -> Color32 {
    let hash = stat_hash(key);
    let mut rng = StdRng::seed_from_u64(hash);
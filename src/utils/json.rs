@@ -0,0 +1,22 @@
+use std::fmt::Write;
+
+/// quotes and escapes a string for hand written json
+pub(crate) fn json_string(text: &str) -> String {
+   let mut out = String::with_capacity(text.len() + 2);
+   out.push('"');
+
+   for c in text.chars() {
+      match c {
+         '"' => out.push_str("\\\""),
+         '\\' => out.push_str("\\\\"),
+         '\n' => out.push_str("\\n"),
+         '\r' => out.push_str("\\r"),
+         '\t' => out.push_str("\\t"),
+         c if (c as u32) < 0x20 => { let _ = write!(out, "\\u{:04x}", c as u32); }
+         c => out.push(c),
+      }
+   }
+
+   out.push('"');
+   out
+}
@@ -1,4 +1,4 @@
-/// alternative to function macro
+/// alternative to function macro, the event ends even if the block returns early or panics
 /// ```
 ///
 /// use triglyceride::{time_event_mac, init_profiler, Settings};
@@ -14,21 +14,65 @@
 /// 
 ///
 /// ```
+#[cfg(not(feature = "disabled"))]
 #[macro_export]
 macro_rules! time_event_mac {
     ($profiler: ident, $name: literal, $code: block) => {
-       triglyceride::open_profiler(&$profiler, |mut p| p.time_event_start($name));
+       {
+          let _triglyceride_scope_guard = triglyceride::scope(&$profiler, $name);
+          $code
+       }
+    };
+}
 
+/// compiled out by the ``disabled`` feature, only the block is left
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! time_event_mac {
+    ($profiler: ident, $name: literal, $code: block) => {
        $code
+    };
+}
+
 
-       triglyceride::open_profiler(&$profiler, |mut p| p.time_event_end($name));
+/// times the rest of the enclosing scope as an event, ending it when the scope exits for any reason
+/// ```
+/// use triglyceride::{scope, init_profiler, Settings};
+/// init_profiler!(PROF, Settings::default());
+///
+/// fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+///    scope!(PROF, "PARSE");
+///
+///    let value = input.parse::<u32>()?;
+///    Ok(value * 2)
+/// }
+///
+/// fn main() {
+///    let _ = parse("nope");
+///    let _ = parse("21");
+/// }
+/// ```
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! scope {
+    ($profiler: ident, $name: literal) => {
+       let _triglyceride_scope_guard = triglyceride::scope(&$profiler, $name);
     };
 }
 
+/// compiled out by the ``disabled`` feature
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! scope {
+    ($profiler: ident, $name: literal) => {};
+}
+
 
 
 /// initialized a ``public`` profiler static taking a name and settings as an input,
-/// is used by all profiling functions 
+/// is used by all profiling functions,
+/// with the ``disabled`` feature the static is still declared so ui and export code keeps compiling,
+/// but it's lazy and nothing touches it unless the app opens it itself
 /// ```
 /// use triglyceride::{init_profiler, Settings};
 /// 
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+use lazy_static::lazy_static;
+
+use crate::StatString;
+
+
+lazy_static! {
+   /// ``(type_name, fn_name)`` to ``type_name::fn_name``
+   static ref METHOD_NAMES: RwLock<HashMap<(&'static str, &'static str), StatString>> = RwLock::default();
+}
+
+/// internal function, the default event name of a method, ``path::to::Type::fn_name``,
+/// leaked once per type so every instance of a generic impl or default trait method gets its own
+/// ```
+/// use std::marker::PhantomData;
+/// use triglyceride::{init_profiler, open_profiler, time_event, Settings};
+///
+/// init_profiler!(PROF, Settings::default());
+///
+/// struct A;
+/// impl A {
+///    #[time_event(PROF)]
+///    fn new() -> Self { A }
+///
+///    #[time_event(PROF)]
+///    fn update(&self) {}
+/// }
+///
+/// struct B;
+/// impl B {
+///    #[time_event(PROF)]
+///    fn new() -> Self { B }
+/// }
+///
+/// struct Wrapper<T>(PhantomData<T>);
+/// impl<T> Wrapper<T> {
+///    #[time_event(PROF)]
+///    fn run(&self) {}
+/// }
+///
+/// #[time_event(PROF)]
+/// fn free_function() {}
+///
+/// fn main() {
+///    A::new().update();
+///    B::new();
+///    Wrapper::<u8>(PhantomData).run();
+///    Wrapper::<u16>(PhantomData).run();
+///    free_function();
+///
+///    open_profiler(&PROF, |mut p| {
+///       let lane = p.current_thread();
+///       # if cfg!(feature = "disabled") { return; }
+///       let mut names: Vec<_> = lane.all_profiles.keys().copied().collect();
+///       names.sort();
+///
+///       let here = module_path!();
+///       assert_eq!(names, [
+///          format!("{here}::A::new"),
+///          format!("{here}::A::update"),
+///          format!("{here}::B::new"),
+///          format!("{here}::Wrapper<u16>::run"),
+///          format!("{here}::Wrapper<u8>::run"),
+///          format!("{here}::free_function"),
+///       ]);
+///    });
+/// }
+/// ```
+#[doc(hidden)]
+pub fn method_event_name(type_name: &'static str, fn_name: &'static str) -> StatString {
+   if let Some(name) = METHOD_NAMES.read().unwrap_or_else(PoisonError::into_inner).get(&(type_name, fn_name)) {
+      return name;
+   }
+
+   let mut names = METHOD_NAMES.write().unwrap_or_else(PoisonError::into_inner);
+   names.entry((type_name, fn_name)).or_insert_with(|| Box::leak(format!("{type_name}::{fn_name}").into_boxed_str()))
+}
@@ -1,60 +1,126 @@
 use std::collections::HashMap;
 use crate::StatString;
 
+/// a function together with the chain of parents it was called from, interned per thread,
+/// the same function under two different parents gets two different ids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PathId(pub(crate) usize);
+impl PathId {
+   pub fn index(&self) -> usize {
+      self.0
+   }
+}
+
+/// every call path seen on a thread, ids are never reused so timings can be kept per path
 #[derive(Debug, Default)]
+pub struct CallPaths {
+   ids: HashMap<(Option<PathId>, StatString), PathId>,
+
+   /// ``(name, parent)`` indexed by ``PathId``
+   entries: Vec<(StatString, Option<PathId>)>,
+}
+impl CallPaths {
+   pub fn intern(&mut self, parent: Option<PathId>, name: StatString) -> PathId {
+      if let Some(id) = self.ids.get(&(parent, name)) {
+         return *id;
+      }
+
+      let id = PathId(self.entries.len());
+      self.entries.push((name, parent));
+      self.ids.insert((parent, name), id);
+      id
+   }
+
+   /// the id of a path that was already interned
+   pub fn get(&self, parent: Option<PathId>, name: StatString) -> Option<PathId> {
+      self.ids.get(&(parent, name)).copied()
+   }
+
+   pub fn name(&self, id: PathId) -> StatString {
+      self.entries[id.0].0
+   }
+
+   pub fn parent(&self, id: PathId) -> Option<PathId> {
+      self.entries[id.0].1
+   }
+
+   /// function names from the outermost caller down to ``id``
+   pub fn chain(&self, id: PathId) -> Vec<StatString> {
+      let mut chain = vec![];
+      let mut next = Some(id);
+      while let Some(id) = next {
+         chain.push(self.name(id));
+         next = self.parent(id);
+      }
+
+      chain.reverse();
+      chain
+   }
+
+   pub fn len(&self) -> usize {
+      self.entries.len()
+   }
+
+   pub fn is_empty(&self) -> bool {
+      self.entries.is_empty()
+   }
+}
+
+#[derive(Debug)]
 pub struct TreeNode {
    pub name: StatString,
-   pub parent: Option<StatString>,
-   pub children: Vec<StatString>,
+   pub path: PathId,
+   pub parent: Option<PathId>,
+   pub children: Vec<PathId>,
 }
 impl TreeNode {
-   pub fn new(id: StatString) -> Self {
+   pub fn new(path: PathId, name: StatString) -> Self {
       Self {
-         name: id,
+         name,
+         path,
          parent: None,
          children: Vec::new(),
       }
    }
 }
 
-/// simple internal tree for graphing relationships
+/// simple internal tree for graphing relationships, nodes are call paths so a function
+/// called from two parents shows up under both
 #[derive(Debug, Default)]
 pub struct Tree {
-   pub nodes: HashMap<StatString, TreeNode>,
-   pub root: Option<StatString>,
+   pub nodes: HashMap<PathId, TreeNode>,
+   pub root: Option<PathId>,
 }
 
 impl Tree {
-   pub fn set_root(&mut self, id: StatString) {
-      if !self.nodes.contains_key(id) {
-         let node = TreeNode::new(id);
-         self.nodes.insert(id, node);
-      }
-      self.root = Some(id);
+   pub fn set_root(&mut self, path: PathId, name: StatString) {
+      self.nodes.entry(path).or_insert_with(|| TreeNode::new(path, name));
+      self.root = Some(path);
    }
 
-   pub fn add_child(&mut self, parent_id: StatString, child_id: StatString) {
-      if !self.nodes.contains_key(parent_id) {
-         let parent_node = TreeNode::new(parent_id);
-         self.nodes.insert(parent_id, parent_node);
-      }
+   /// adds ``child`` under ``parent``, repeat calls of the same path share one node
+   pub fn add_child(&mut self, parent: PathId, child: PathId, name: StatString) {
+      self.nodes.entry(child).or_insert_with(|| TreeNode {
+         name,
+         path: child,
+         parent: Some(parent),
+         children: Vec::new(),
+      });
 
-      if !self.nodes.contains_key(child_id) {
-         let child_node = TreeNode {
-            name: child_id,
-            parent: Some(parent_id),
-            children: Vec::new(),
-         };
-         self.nodes.insert(child_id, child_node);
+      if let Some(parent_node) = self.nodes.get_mut(&parent) {
+         if !parent_node.children.contains(&child) {
+            parent_node.children.push(child);
+         }
       }
+   }
 
-      if let Some(parent_node) = self.nodes.get_mut(parent_id) {
-         parent_node.children.push(child_id);
-      }
+   /// name of the root node
+   pub fn root_name(&self) -> Option<StatString> {
+      Some(self.nodes.get(&self.root?)?.name)
    }
 
    pub fn clear(&mut self) {
       self.nodes.clear();
       self.root = None;
    }
-}
\ No newline at end of file
+}
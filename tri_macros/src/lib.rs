@@ -2,58 +2,46 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-use quote::quote;
-use syn::{Expr, ItemFn, parse_macro_input};
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{Error, Expr, FnArg, ItemFn, parse_macro_input, Signature, Token};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 
 
 /// times an event and adds it to the function tree,
-/// takes a reference to a profiler static and a str name
+/// takes a reference to a profiler static and optionally a str name,
+/// without a name it's ``module_path!()::fn_name``, or ``path::to::Type::fn_name`` for fns taking ``self`` or naming ``Self``,
+/// other associated fns can't be told apart from free fns, so two with the same name in one module share an event,
+/// the event is ended by a scope guard so early returns and panics are still timed,
+/// an ``async fn`` is timed while it runs with ``triglyceride::Instrumented`` instead,
+/// its poll time under the name and its wall time under ``NAME (wall)``, outside the tree
 /// ```
-/// use triglyceride::{init_profiler, time_event};
-/// 
+/// use triglyceride::{init_profiler, time_event, Settings};
+///
 /// init_profiler!(PROF, Settings::default());
-/// 
+///
 /// #[time_event(PROF, "MAIN")]
 /// fn main_update_function() {}
-/// 
 ///
-/// #[time_event(PROF, "INNER")]
+///
+/// // "<module path>::inner_function"
+/// #[time_event(PROF)]
 /// fn inner_function() {}
 /// ```
 #[proc_macro_attribute]
 pub fn time_event(attr: TokenStream, input: TokenStream) -> TokenStream {
-   let input = parse_macro_input!(input as ItemFn);
-   let attrs: Vec<Expr> = parse_macro_input!(attr with syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated).into_iter().collect();
-
-
-   let profiler = attrs.get(0).unwrap();
-   let name = attrs.get(1).unwrap();
-
-
-   let fn_vis = &input.vis;
-   let fn_attar = &input.attrs;
-   let fn_sig = &input.sig;
-   let fn_block = &input.block;
-
-   let expanded = quote! {
-        #(#fn_attar)*
-        #fn_vis #fn_sig {
-            triglyceride::open_profiler(&#profiler, |mut p| p.time_event_start(#name));
-            let a_name_for_veriable_that_proboly_wont_be_used = #fn_block;
-            triglyceride::open_profiler(&#profiler, |mut p| p.time_event_end(#name));
-            a_name_for_veriable_that_proboly_wont_be_used
-        }
-    };
-
-   TokenStream::from(expanded)
+   instrument(attr, input, "time_event", quote!(triglyceride::scope))
 }
 
 
 /// times an event without adding it to the function tree,
 /// can be viewed in ``PerformanceProfiler::list_all_functions``
-/// takes a reference to a profiler static and a str name
+/// takes a reference to a profiler static and optionally a str name, named like ``time_event`` without one,
+/// an ``async fn`` is timed the same way as with ``time_event``
 /// ```
-/// use triglyceride::{init_profiler, time_lone_function};
+/// use triglyceride::{init_profiler, time_lone_function, Settings};
 ///
 /// init_profiler!(PROF, Settings::default());
 ///
@@ -67,28 +55,103 @@ pub fn time_event(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 pub fn time_lone_function(attr: TokenStream, input: TokenStream) -> TokenStream {
-   let input = parse_macro_input!(input as ItemFn);
-   let attrs: Vec<Expr> = parse_macro_input!(attr with syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated).into_iter().collect();
+   instrument(attr, input, "time_lone_function", quote!(triglyceride::lone_scope))
+}
 
 
-   let profiler = attrs.get(0).unwrap();
-   let name = attrs.get(1).unwrap();
+/// wraps the body of ``input`` in ``scope_fn``, or an instrumented future for an ``async fn``
+fn instrument(attr: TokenStream, input: TokenStream, macro_name: &str, scope_fn: TokenStream2) -> TokenStream {
+   let function = parse_macro_input!(input as ItemFn);
+
+   let (profiler, name) = match parse_args(attr, macro_name, &function.sig) {
+      Ok(args) => args,
+      Err(error) => return error.into_compile_error().into(),
+   };
+
+   // checked anyway so turning the feature off never breaks the build
+   if cfg!(feature = "disabled") {
+      return function.into_token_stream().into();
+   }
+
+   let fn_vis = &function.vis;
+   let fn_attar = &function.attrs;
+   let fn_sig = &function.sig;
+   let fn_block = &function.block;
+
+   let expanded = match fn_sig.asyncness {
+      // the body runs as an instrumented future so the time is taken while it's polled, not while it's built
+      Some(_) => quote! {
+         #(#fn_attar)*
+         #fn_vis #fn_sig {
+            triglyceride::ProfileFuture::profile(async move #fn_block, &#profiler, #name).await
+         }
+      },
+      // the statements are spliced in, a nested block as the tail would warn about unnecessary braces
+      None => {
+         let fn_stmts = &fn_block.stmts;
+         quote! {
+            #(#fn_attar)*
+            #fn_vis #fn_sig {
+               let _triglyceride_scope_guard = #scope_fn(&#profiler, #name);
+               #(#fn_stmts)*
+            }
+         }
+      }
+   };
 
+   TokenStream::from(expanded)
+}
 
-   let fn_vis = &input.vis;
-   let fn_attar = &input.attrs;
-   let fn_sig = &input.sig;
-   let fn_block = &input.block;
+/// the profiler static and the event name, the name is generated if it's left out
+fn parse_args(attr: TokenStream, macro_name: &str, sig: &Signature) -> syn::Result<(Expr, TokenStream2)> {
+   let args = Punctuated::<Expr, Token![,]>::parse_terminated.parse(attr)?;
+   let mut args = args.into_iter();
 
-   let expanded = quote! {
-        #(#fn_attar)*
-        #fn_vis #fn_sig {
-            triglyceride::open_profiler(&#profiler, |mut p| p.start_time_function(#name));
-            let a_name_for_veriable_that_proboly_wont_be_used = #fn_block;
-            triglyceride::open_profiler(&#profiler, |mut p| p.end_time_function(#name));
-            a_name_for_veriable_that_proboly_wont_be_used
-        }
-    };
+   let Some(profiler) = args.next() else {
+      return Err(Error::new(
+         Span::call_site(),
+         format!("expected a profiler static and an optional name, e.g. `#[{macro_name}(PROF)]` or `#[{macro_name}(PROF, \"NAME\")]`"),
+      ));
+   };
 
-   TokenStream::from(expanded)
-}
\ No newline at end of file
+   let name = match args.next() {
+      Some(name) => name.into_token_stream(),
+      None => default_name(sig),
+   };
+
+   if let Some(extra) = args.next() {
+      return Err(Error::new(extra.span(), "unexpected argument, only a profiler static and a name are taken"));
+   }
+
+   Ok((profiler, name))
+}
+
+/// ``module_path!()::fn_name``, fns taking ``self`` or naming ``Self`` use the path of ``Self`` instead,
+/// so each type a generic impl or a default trait method runs for is named on its own,
+/// the name of the first type is kept next to the function so only other types look it up
+fn default_name(sig: &Signature) -> TokenStream2 {
+   let fn_name = sig.ident.to_string();
+
+   let is_method = sig.inputs.iter().any(|input| matches!(input, FnArg::Receiver(_)));
+   if !is_method && !names_self(sig.to_token_stream()) {
+      return quote!(::core::concat!(::core::module_path!(), "::", #fn_name));
+   }
+
+   quote! {{
+      static TRIGLYCERIDE_NAME: ::std::sync::OnceLock<(&'static str, &'static str)> = ::std::sync::OnceLock::new();
+      let type_name = ::std::any::type_name::<Self>();
+      match *TRIGLYCERIDE_NAME.get_or_init(|| (type_name, triglyceride::method_event_name(type_name, #fn_name))) {
+         (first, name) if first == type_name => name,
+         _ => triglyceride::method_event_name(type_name, #fn_name),
+      }
+   }}
+}
+
+/// ``true`` if ``tokens`` mention ``Self`` anywhere, only fns inside an impl or a trait can
+fn names_self(tokens: TokenStream2) -> bool {
+   tokens.into_iter().any(|token| match token {
+      TokenTree::Ident(ident) => ident == "Self",
+      TokenTree::Group(group) => names_self(group.stream()),
+      _ => false,
+   })
+}